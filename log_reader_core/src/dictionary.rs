@@ -0,0 +1,137 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::record::{Field, Record};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MessageTemplate {
+    pub name: String,
+    pub template: String,
+    pub description: String,
+}
+
+/// Names for the numeric values of one product's logs, loaded from a JSON file like
+/// `{"product": "...", "log_levels": {"3": "Warning"}, "facilities": {...},
+/// "message_ids": {"17": {"name": "...", "template": "...", "description": "..."}}}`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Dictionary {
+    pub product: String,
+    pub log_levels: BTreeMap<u8, String>,
+    pub facilities: BTreeMap<u32, String>,
+    pub message_ids: BTreeMap<u32, MessageTemplate>,
+
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+impl Dictionary {
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let mut dictionary: Self = serde_json::from_slice(&fs::read(path)?)?;
+        dictionary.path = Some(path.to_owned());
+        Ok(dictionary)
+    }
+
+    pub fn name(&self, field: Field, record: &Record) -> Option<&str> {
+        match field {
+            Field::LogLevel => self.log_levels.get(&record.log_level),
+            Field::Facility => self.facilities.get(&record.facility),
+            Field::MessageId => self.message_ids.get(&record.message_id).map(|t| &t.name),
+            _ => None,
+        }
+        .map(String::as_str)
+        .filter(|name| !name.is_empty())
+    }
+
+    /// The value as shown in the table, the name when the dictionary knows one.
+    pub fn display(&self, field: Field, record: &Record) -> String {
+        match self.name(field, record) {
+            Some(name) => name.to_owned(),
+            None => field.value(record),
+        }
+    }
+
+    /// Raw value together with its name, and the template and description for message IDs.
+    pub fn describe(&self, field: Field, record: &Record) -> Option<String> {
+        let name = self.name(field, record)?;
+        let mut txt = format!("{} = {}", field.value(record), name);
+        if field == Field::MessageId
+            && let Some(template) = self.message_ids.get(&record.message_id)
+        {
+            for line in [&template.template, &template.description] {
+                if !line.is_empty() {
+                    txt.push('\n');
+                    txt.push_str(line);
+                }
+            }
+        }
+        Some(txt)
+    }
+
+    /// Parses a filter value, which is either the number itself or a name from the dictionary.
+    pub fn resolve(&self, field: Field, value: &str) -> Option<u32> {
+        if let Ok(num) = value.parse() {
+            return Some(num);
+        }
+
+        let matches = |name: &String| name.eq_ignore_ascii_case(value);
+        match field {
+            Field::LogLevel => self
+                .log_levels
+                .iter()
+                .find(|(_, name)| matches(name))
+                .map(|(&num, _)| num as u32),
+            Field::Facility => self
+                .facilities
+                .iter()
+                .find(|(_, name)| matches(name))
+                .map(|(&num, _)| num),
+            Field::MessageId => self
+                .message_ids
+                .iter()
+                .find(|(_, template)| matches(&template.name))
+                .map(|(&num, _)| num),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn test_resolve_and_describe() {
+        let dictionary: Dictionary = serde_json::from_str(
+            r#"{
+                "product": "Test",
+                "log_levels": {"3": "Warning"},
+                "message_ids": {"17": {"name": "DiskFull", "template": "Disk {0} is full", "description": "Free some space"}}
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(dictionary.resolve(Field::LogLevel, "warning"), Some(3));
+        assert_eq!(dictionary.resolve(Field::LogLevel, "4"), Some(4));
+        assert_eq!(dictionary.resolve(Field::LogLevel, "Error"), None);
+        assert_eq!(dictionary.resolve(Field::MessageId, "DiskFull"), Some(17));
+
+        let record = Record {
+            message_id: 17,
+            ..testing::record()
+        };
+        assert_eq!(dictionary.display(Field::LogLevel, &record), "Warning");
+        assert_eq!(dictionary.display(Field::Facility, &record), "5");
+        assert_eq!(
+            dictionary.describe(Field::MessageId, &record).unwrap(),
+            "17 = DiskFull\nDisk {0} is full\nFree some space"
+        );
+    }
+}
@@ -0,0 +1,557 @@
+use std::fmt;
+
+use chrono::{DateTime, Local};
+
+use serde::{Deserialize, Serialize};
+
+use crate::dictionary::Dictionary;
+use crate::record::{Field, Record};
+
+pub type RecordFilter<'a> = Box<dyn Fn(&Record) -> bool + 'a>;
+
+/// Values of all column filters of the table and the values hidden or kept from its
+/// context menu. Empty values do not filter.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FilterValues {
+    pub log_name: String,
+    pub date: String,
+    // Upper end of a date range, records logged before it are kept
+    pub date_before: String,
+    pub computer_name: String,
+    pub process_id: String,
+    pub process_user: String,
+    pub module_name: String,
+    pub message_id: String,
+    pub log_level: String,
+    pub facility: String,
+    pub log_message: String,
+    // Values hidden with "exclude this value", matched against the shown cell value
+    pub excluded: Vec<(Field, String)>,
+    // Values kept with "filter by this value", at most one per field, equal to the shown
+    // cell value
+    pub kept: Vec<(Field, String)>,
+    // Record IDs kept by "filter to selection", empty shows all
+    pub included: Vec<u64>,
+    // How the filter value of a column is matched, when not the default of the field
+    pub modes: Vec<(Field, FilterMode)>,
+}
+
+/// How the filter value typed in a column header is matched against its cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FilterMode {
+    Contains,
+    Equals,
+    Excludes,
+}
+
+impl FilterMode {
+    pub const ALL: [FilterMode; 3] = [
+        FilterMode::Contains,
+        FilterMode::Equals,
+        FilterMode::Excludes,
+    ];
+
+    /// Numbers are compared whole, text is searched in.
+    pub fn default_for(field: Field) -> Self {
+        if is_numeric(field) {
+            FilterMode::Equals
+        } else {
+            FilterMode::Contains
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FilterMode::Contains => "contains",
+            FilterMode::Equals => "equals",
+            FilterMode::Excludes => "excludes",
+        }
+    }
+}
+
+impl FilterValues {
+    /// Text filter values to highlight in the cells of their column.
+    pub fn terms(&self) -> Vec<(Field, String)> {
+        Field::ALL
+            .into_iter()
+            .filter(|&field| field != Field::Date && self.mode(field) != FilterMode::Excludes)
+            .map(|field| (field, self.get(field).clone()))
+            .filter(|(_, value)| !value.is_empty())
+            .collect()
+    }
+
+    pub fn get(&self, field: Field) -> &String {
+        match field {
+            Field::Id => &self.log_name,
+            Field::Date => &self.date,
+            Field::ComputerName => &self.computer_name,
+            Field::ProcessId => &self.process_id,
+            Field::ProcessUser => &self.process_user,
+            Field::ModuleName => &self.module_name,
+            Field::MessageId => &self.message_id,
+            Field::LogLevel => &self.log_level,
+            Field::Facility => &self.facility,
+            Field::LogMessage => &self.log_message,
+        }
+    }
+
+    pub fn get_mut(&mut self, field: Field) -> &mut String {
+        match field {
+            Field::Id => &mut self.log_name,
+            Field::Date => &mut self.date,
+            Field::ComputerName => &mut self.computer_name,
+            Field::ProcessId => &mut self.process_id,
+            Field::ProcessUser => &mut self.process_user,
+            Field::ModuleName => &mut self.module_name,
+            Field::MessageId => &mut self.message_id,
+            Field::LogLevel => &mut self.log_level,
+            Field::Facility => &mut self.facility,
+            Field::LogMessage => &mut self.log_message,
+        }
+    }
+
+    /// How the filter value of `field` is matched. The date is always a range.
+    pub fn mode(&self, field: Field) -> FilterMode {
+        self.modes
+            .iter()
+            .find(|(f, _)| *f == field)
+            .map_or(FilterMode::default_for(field), |&(_, mode)| mode)
+    }
+
+    pub fn set_mode(&mut self, field: Field, mode: FilterMode) {
+        self.modes.retain(|(f, _)| *f != field);
+        if mode != FilterMode::default_for(field) {
+            self.modes.push((field, mode));
+        }
+    }
+
+    /// Shows only the records whose cell of `field` shows `value`, replacing the value kept
+    /// for the field before.
+    pub fn keep_value(&mut self, field: Field, value: String) {
+        self.excluded
+            .retain(|excluded| *excluded != (field, value.clone()));
+        self.kept.retain(|(kept_field, _)| *kept_field != field);
+        self.kept.push((field, value));
+    }
+
+    /// Hides the records whose cell of `field` shows `value`, on top of the values hidden before.
+    pub fn exclude_value(&mut self, field: Field, value: String) {
+        self.kept.retain(|kept| *kept != (field, value.clone()));
+        if !self.excluded.contains(&(field, value.clone())) {
+            self.excluded.push((field, value));
+        }
+    }
+
+    /// The column filters as conditions: dates from the value on and before `date_before`,
+    /// the other fields as their filter mode says.
+    pub fn conditions(&self) -> Vec<Condition> {
+        Field::ALL
+            .into_iter()
+            .filter(|&field| !self.get(field).is_empty())
+            .map(|field| Condition {
+                field,
+                op: match (field, self.mode(field)) {
+                    (Field::Date, _) => CompareOp::Ge,
+                    (_, FilterMode::Contains) => CompareOp::Contains,
+                    (_, FilterMode::Equals) => CompareOp::Eq,
+                    (_, FilterMode::Excludes) if is_numeric(field) => CompareOp::Ne,
+                    (_, FilterMode::Excludes) => CompareOp::NotContains,
+                },
+                value: self.get(field).clone(),
+            })
+            .chain((!self.date_before.is_empty()).then(|| Condition {
+                field: Field::Date,
+                op: CompareOp::Lt,
+                value: self.date_before.clone(),
+            }))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Contains,
+    NotContains,
+}
+
+impl CompareOp {
+    // Longer operators first, so `>=` is not read as `>`
+    const ALL: [(&'static str, CompareOp); 8] = [
+        ("!=", CompareOp::Ne),
+        ("!~", CompareOp::NotContains),
+        (">=", CompareOp::Ge),
+        ("<=", CompareOp::Le),
+        ("=", CompareOp::Eq),
+        ("~", CompareOp::Contains),
+        (">", CompareOp::Gt),
+        ("<", CompareOp::Lt),
+    ];
+
+    fn holds(&self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering::*;
+        match self {
+            CompareOp::Eq => ordering == Equal,
+            CompareOp::Ne => ordering != Equal,
+            CompareOp::Lt => ordering == Less,
+            CompareOp::Le => ordering != Greater,
+            CompareOp::Gt => ordering == Greater,
+            CompareOp::Ge => ordering != Less,
+            CompareOp::Contains | CompareOp::NotContains => false,
+        }
+    }
+}
+
+impl fmt::Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (op, _) = CompareOp::ALL.iter().find(|(_, op)| op == self).unwrap();
+        f.write_str(op)
+    }
+}
+
+/// One `field op value` term of a filter query, like `level>=3` or `module~Net`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    pub field: Field,
+    pub op: CompareOp,
+    pub value: String,
+}
+
+fn is_numeric(field: Field) -> bool {
+    matches!(
+        field,
+        Field::Id | Field::ProcessId | Field::MessageId | Field::LogLevel | Field::Facility
+    )
+}
+
+fn number(field: Field, record: &Record) -> u64 {
+    match field {
+        Field::Id => record.id,
+        Field::ProcessId => record.process_id as u64,
+        Field::MessageId => record.message_id as u64,
+        Field::LogLevel => record.log_level as u64,
+        Field::Facility => record.facility as u64,
+        _ => 0,
+    }
+}
+
+/// The field named in a query, by its short name or its column name without spaces.
+pub fn parse_field(name: &str) -> Option<Field> {
+    let name = name.to_ascii_lowercase().replace([' ', '_', '-'], "");
+    let field = match name.as_str() {
+        "id" => Field::Id,
+        "date" | "time" => Field::Date,
+        "computer" | "host" => Field::ComputerName,
+        "pid" | "process" => Field::ProcessId,
+        "user" => Field::ProcessUser,
+        "module" => Field::ModuleName,
+        "msgid" => Field::MessageId,
+        "level" => Field::LogLevel,
+        "facility" => Field::Facility,
+        "message" | "msg" => Field::LogMessage,
+        _ => {
+            return Field::ALL
+                .into_iter()
+                .find(|field| field.name().to_ascii_lowercase().replace(' ', "") == name);
+        }
+    };
+    Some(field)
+}
+
+/// Splits the query at spaces outside of double quotes, the quotes are dropped.
+fn split_terms(query: &str) -> Result<Vec<String>, String> {
+    let mut terms = vec![];
+    let mut term = String::new();
+    let mut quoted = false;
+    for c in query.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !term.is_empty() {
+                    terms.push(std::mem::take(&mut term));
+                }
+            }
+            c => term.push(c),
+        }
+    }
+    if quoted {
+        return Err(String::from("Unclosed quote in the filter"));
+    }
+    if !term.is_empty() {
+        terms.push(term);
+    }
+    Ok(terms)
+}
+
+/// Parses space separated conditions that all have to hold, e.g.
+/// `level>=3 module~Net message!~"timed out"`.
+pub fn parse_query(query: &str) -> Result<Vec<Condition>, String> {
+    split_terms(query)?
+        .into_iter()
+        .map(|term| {
+            let start = term
+                .find(['!', '<', '>', '=', '~'])
+                .ok_or_else(|| format!("Missing operator in '{}'", term))?;
+            let (name, rest) = term.split_at(start);
+            let (op_text, op) = CompareOp::ALL
+                .into_iter()
+                .find(|(op_text, _)| rest.starts_with(op_text))
+                .ok_or_else(|| format!("Unknown operator in '{}'", term))?;
+            let field = parse_field(name).ok_or_else(|| format!("Unknown field '{}'", name))?;
+            Ok(Condition {
+                field,
+                op,
+                value: rest[op_text.len()..].to_owned(),
+            })
+        })
+        .collect()
+}
+
+impl Condition {
+    /// The check for one record. Numbers accept dictionary names, e.g. `level>=Warning`,
+    /// and dates have to be in the RFC 3339 format.
+    pub fn filter<'a>(&'a self, dictionary: &Dictionary) -> Result<RecordFilter<'a>, String> {
+        let op = self.op;
+        let field = self.field;
+        if matches!(op, CompareOp::Contains | CompareOp::NotContains) {
+            let txt = self.value.as_str();
+            let keep = op == CompareOp::Contains;
+            return Ok(match field {
+                Field::ComputerName => Box::new(move |r| r.computer_name.contains(txt) == keep),
+                Field::ProcessUser => Box::new(move |r| r.process_user.contains(txt) == keep),
+                Field::ModuleName => Box::new(move |r| r.module_name.contains(txt) == keep),
+                Field::LogMessage => Box::new(move |r| r.log_message.contains(txt) == keep),
+                _ => Box::new(move |r| field.value(r).contains(txt) == keep),
+            });
+        }
+
+        let invalid = || format!("Invalid value for {} in '{}'", field.name(), self);
+        if field == Field::Date {
+            let date = self
+                .value
+                .parse::<DateTime<Local>>()
+                .map_err(|_| invalid())?;
+            Ok(Box::new(move |r| op.holds(r.date.cmp(&date))))
+        } else if is_numeric(field) {
+            let num = match field {
+                Field::MessageId | Field::LogLevel | Field::Facility => {
+                    dictionary.resolve(field, &self.value).map(u64::from)
+                }
+                _ => self.value.parse().ok(),
+            }
+            .ok_or_else(invalid)?;
+            Ok(Box::new(move |r| op.holds(number(field, r).cmp(&num))))
+        } else {
+            let txt = self.value.as_str();
+            Ok(Box::new(move |r| {
+                op.holds(field.value(r).as_str().cmp(txt))
+            }))
+        }
+    }
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.field.name(), self.op, self.value)
+    }
+}
+
+/// The checks of all conditions, or the first condition whose value cannot be compared.
+pub fn compile<'a>(
+    conditions: &'a [Condition],
+    dictionary: &Dictionary,
+) -> Result<Vec<RecordFilter<'a>>, String> {
+    conditions
+        .iter()
+        .map(|condition| condition.filter(dictionary))
+        .collect()
+}
+
+/// The checks of the column filters, kept and excluded values and the kept records. Conditions
+/// with values that cannot be compared, like a half typed date, are left out.
+pub fn build<'a>(
+    values: &'a FilterValues,
+    conditions: &'a [Condition],
+    dictionary: &'a Dictionary,
+) -> Vec<RecordFilter<'a>> {
+    let mut checks: Vec<RecordFilter> = conditions
+        .iter()
+        .filter_map(|condition| condition.filter(dictionary).ok())
+        .collect();
+
+    // Kept and excluded values are compared with the cell as shown, names included
+    if !values.kept.is_empty() {
+        let kept = &values.kept;
+        checks.push(Box::new(move |item: &Record| {
+            kept.iter()
+                .all(|(field, value)| dictionary.display(*field, item) == *value)
+        }));
+    }
+
+    if !values.excluded.is_empty() {
+        let excluded = &values.excluded;
+        checks.push(Box::new(move |item: &Record| {
+            !excluded
+                .iter()
+                .any(|(field, value)| dictionary.display(*field, item) == *value)
+        }));
+    }
+
+    if !values.included.is_empty() {
+        let included = &values.included;
+        checks.push(Box::new(move |item: &Record| {
+            included.binary_search(&item.id).is_ok()
+        }));
+    }
+    checks
+}
+
+pub fn matches(checks: &[RecordFilter], record: &Record) -> bool {
+    checks.iter().all(|check| check(record))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn test_query() {
+        let conditions = parse_query(r#"level>=3 Module~Net msg!~"timed out""#).unwrap();
+        assert_eq!(
+            conditions[0],
+            Condition {
+                field: Field::LogLevel,
+                op: CompareOp::Ge,
+                value: String::from("3"),
+            }
+        );
+        assert_eq!(conditions[1].field, Field::ModuleName);
+        assert_eq!(conditions[2].op, CompareOp::NotContains);
+        assert_eq!(conditions[2].value, "timed out");
+
+        assert!(parse_query("level").is_err());
+        assert!(parse_query("colour=red").is_err());
+        assert!(parse_query("msg~\"open").is_err());
+
+        let record = |log_level, module_name: &str, log_message: &str| Record {
+            log_level,
+            module_name: module_name.to_owned(),
+            log_message: log_message.to_owned(),
+            ..testing::record()
+        };
+        let dictionary = Dictionary::default();
+        let checks = compile(&conditions, &dictionary).unwrap();
+        assert!(matches(&checks, &record(4, "NetIO", "sent")));
+        assert!(!matches(&checks, &record(2, "NetIO", "sent")));
+        assert!(!matches(&checks, &record(4, "Disk", "sent")));
+        assert!(!matches(&checks, &record(4, "NetIO", "send timed out")));
+
+        let bad_level = parse_query("level>=Loud").unwrap();
+        assert!(bad_level[0].filter(&dictionary).is_err());
+
+        let values = FilterValues {
+            date: String::from("2024-05-01T10:00:00+02:00"),
+            date_before: String::from("2024-05-01T11:00:00+02:00"),
+            module_name: String::from("Net"),
+            ..FilterValues::default()
+        };
+        let ops: Vec<(Field, CompareOp)> = values
+            .conditions()
+            .iter()
+            .map(|c| (c.field, c.op))
+            .collect();
+        assert_eq!(
+            ops,
+            vec![
+                (Field::Date, CompareOp::Ge),
+                (Field::ModuleName, CompareOp::Contains),
+                (Field::Date, CompareOp::Lt),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_keep_and_exclude_values() {
+        let dictionary: Dictionary =
+            serde_json::from_str(r#"{"log_levels": {"2": "Error", "3": "Warning"}}"#).unwrap();
+        let record = |log_level, module_name: &str| Record {
+            log_level,
+            module_name: module_name.to_owned(),
+            ..crate::testing::record()
+        };
+        let shown = |values: &FilterValues, record: &Record| {
+            matches(&build(values, &[], &dictionary), record)
+        };
+
+        // Equal to the shown value, names of the dictionary included
+        let mut values = FilterValues::default();
+        values.keep_value(Field::ModuleName, String::from("Net"));
+        values.keep_value(Field::LogLevel, String::from("Warning"));
+        assert!(shown(&values, &record(3, "Net")));
+        assert!(!shown(&values, &record(3, "Network")));
+        assert!(!shown(&values, &record(2, "Net")));
+
+        // Excluding drops the kept value and exclusions stack
+        values.exclude_value(Field::ModuleName, String::from("Net"));
+        values.exclude_value(Field::ModuleName, String::from("Disk"));
+        assert_eq!(
+            values.kept,
+            vec![(Field::LogLevel, String::from("Warning"))]
+        );
+        assert!(!shown(&values, &record(3, "Net")));
+        assert!(!shown(&values, &record(3, "Disk")));
+        assert!(shown(&values, &record(3, "Network")));
+
+        // Keeping a value again lifts its exclusion
+        values.keep_value(Field::ModuleName, String::from("Disk"));
+        assert_eq!(values.excluded.len(), 1);
+        assert!(shown(&values, &record(3, "Disk")));
+    }
+
+    #[test]
+    fn test_filter_modes() {
+        let dictionary = Dictionary::default();
+        let record = |module_name: &str, facility| Record {
+            module_name: module_name.to_owned(),
+            facility,
+            ..crate::testing::record()
+        };
+        let shown = |values: &FilterValues, record: &Record| {
+            let conditions = values.conditions();
+            matches(&build(values, &conditions, &dictionary), record)
+        };
+
+        let mut values = FilterValues {
+            module_name: String::from("Net"),
+            facility: String::from("4"),
+            ..FilterValues::default()
+        };
+        assert!(shown(&values, &record("Network", 4)));
+        assert!(!shown(&values, &record("Network", 14)));
+
+        values.set_mode(Field::ModuleName, FilterMode::Equals);
+        assert!(!shown(&values, &record("Network", 4)));
+        assert!(shown(&values, &record("Net", 4)));
+
+        values.set_mode(Field::ModuleName, FilterMode::Excludes);
+        values.set_mode(Field::Facility, FilterMode::Excludes);
+        assert!(shown(&values, &record("Disk", 14)));
+        assert!(!shown(&values, &record("Disk", 4)));
+        assert!(!shown(&values, &record("Network", 14)));
+        assert!(values.terms().is_empty());
+
+        // The default mode of a field is not stored
+        values.set_mode(Field::Facility, FilterMode::Equals);
+        assert_eq!(
+            values.modes,
+            vec![(Field::ModuleName, FilterMode::Excludes)]
+        );
+    }
+}
@@ -0,0 +1,27 @@
+//! Reading, filtering and indexing log records, shared by the viewer and other tools.
+//!
+//! ```no_run
+//! use log_reader_core::{Dictionary, Record, filter};
+//!
+//! let conditions = filter::parse_query("level>=3 module~Net").unwrap();
+//! let dictionary = Dictionary::default();
+//! let checks = filter::compile(&conditions, &dictionary).unwrap();
+//! for record in Record::records("app.log").unwrap() {
+//!     let record = record.unwrap();
+//!     if filter::matches(&checks, &record) {
+//!         println!("{}", record.log_message);
+//!     }
+//! }
+//! ```
+
+pub mod dictionary;
+pub mod filter;
+pub mod record;
+pub mod search_index;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+
+pub use dictionary::Dictionary;
+pub use filter::{Condition, FilterMode, FilterValues};
+pub use record::{DATE_FORMAT, Field, Record, Records};
+pub use search_index::SearchIndex;
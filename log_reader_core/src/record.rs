@@ -0,0 +1,270 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Lines},
+    path::Path,
+    str::FromStr,
+};
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+pub const DATE_FORMAT: &str = "%d. %m. %Y %H:%M:%S%.3f";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub id: u64,
+    pub date: DateTime<Local>,
+    pub computer_name: String,
+    pub process_id: u32,
+    pub process_user: String,
+    pub module_name: String,
+    pub message_id: u32,
+    pub log_level: u8,
+    pub facility: u32,
+    pub log_message: String,
+}
+
+/// A column of the record, used wherever a field has to be picked at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Field {
+    Id,
+    Date,
+    ComputerName,
+    ProcessId,
+    ProcessUser,
+    ModuleName,
+    MessageId,
+    LogLevel,
+    Facility,
+    LogMessage,
+}
+
+impl Field {
+    pub const ALL: [Field; 10] = [
+        Field::Id,
+        Field::Date,
+        Field::ComputerName,
+        Field::ProcessId,
+        Field::ProcessUser,
+        Field::ModuleName,
+        Field::MessageId,
+        Field::LogLevel,
+        Field::Facility,
+        Field::LogMessage,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Field::Id => "ID",
+            Field::Date => "Date",
+            Field::ComputerName => "Computer Name",
+            Field::ProcessId => "Process ID",
+            Field::ProcessUser => "Process User",
+            Field::ModuleName => "Module Name",
+            Field::MessageId => "Message ID",
+            Field::LogLevel => "Log Level",
+            Field::Facility => "Facility",
+            Field::LogMessage => "Log Message",
+        }
+    }
+
+    /// The value as shown in the table.
+    pub fn value(&self, record: &Record) -> String {
+        match self {
+            Field::Id => record.id.to_string(),
+            Field::Date => record.date.format(DATE_FORMAT).to_string(),
+            Field::ComputerName => record.computer_name.clone(),
+            Field::ProcessId => record.process_id.to_string(),
+            Field::ProcessUser => record.process_user.clone(),
+            Field::ModuleName => record.module_name.clone(),
+            Field::MessageId => record.message_id.to_string(),
+            Field::LogLevel => record.log_level.to_string(),
+            Field::Facility => record.facility.to_string(),
+            Field::LogMessage => record.log_message.clone(),
+        }
+    }
+}
+
+impl Field {
+    pub fn compare(&self, a: &Record, b: &Record) -> std::cmp::Ordering {
+        match self {
+            Field::Id => a.id.cmp(&b.id),
+            Field::Date => a.date.cmp(&b.date),
+            Field::ComputerName => a.computer_name.cmp(&b.computer_name),
+            Field::ProcessId => a.process_id.cmp(&b.process_id),
+            Field::ProcessUser => a.process_user.cmp(&b.process_user),
+            Field::ModuleName => a.module_name.cmp(&b.module_name),
+            Field::MessageId => a.message_id.cmp(&b.message_id),
+            Field::LogLevel => a.log_level.cmp(&b.log_level),
+            Field::Facility => a.facility.cmp(&b.facility),
+            Field::LogMessage => a.log_message.cmp(&b.log_message),
+        }
+    }
+}
+
+impl std::fmt::Display for Field {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+fn parse_number<T: FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| format!("Bad {} '{}'", name, value))
+}
+
+impl Record {
+    /// Parses one line of a log file: date, computer name, process ID, process user, module
+    /// name, message ID, log level, facility and the message, separated by tabs. `\n` in the
+    /// message stands for a line break. `id` is the position of the record in the log.
+    pub fn parse(id: u64, line: &str) -> Result<Record, String> {
+        let mut values = line.splitn(9, '\t');
+        let mut next = |name: &str| values.next().ok_or_else(|| format!("Missing the {}", name));
+
+        let date = next("date")?;
+        let date = NaiveDateTime::parse_from_str(date.trim(), DATE_FORMAT)
+            .ok()
+            .and_then(|date| Local.from_local_datetime(&date).earliest())
+            .ok_or_else(|| format!("Bad date '{}'", date))?;
+        let computer_name = next("computer name")?.to_owned();
+        let process_id = parse_number("process ID", next("process ID")?)?;
+        let process_user = next("process user")?.to_owned();
+        let module_name = next("module name")?.to_owned();
+        let message_id = parse_number("message ID", next("message ID")?)?;
+        let log_level = parse_number("log level", next("log level")?)?;
+        let facility = parse_number("facility", next("facility")?)?;
+        let log_message = next("message")?.replace("\\n", "\n");
+
+        Ok(Record {
+            id,
+            date,
+            computer_name,
+            process_id,
+            process_user,
+            module_name,
+            message_id,
+            log_level,
+            facility,
+            log_message,
+        })
+    }
+
+    /// All records of the log at `path`, in the order they were logged. Empty lines are
+    /// skipped.
+    pub fn read_records(path: impl AsRef<Path>) -> io::Result<Vec<Record>> {
+        Self::records(path)?.collect()
+    }
+
+    /// The records one after another, read from the file as they are asked for, for tools
+    /// that only look at each record once.
+    pub fn records(path: impl AsRef<Path>) -> io::Result<Records> {
+        Ok(Records {
+            lines: BufReader::new(File::open(path)?).lines(),
+            line_no: 0,
+            next_id: 0,
+        })
+    }
+}
+
+/// Reads the records of a log file line by line, see [`Record::records`].
+pub struct Records {
+    lines: Lines<BufReader<File>>,
+    line_no: usize,
+    next_id: u64,
+}
+
+impl Iterator for Records {
+    type Item = io::Result<Record>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e)),
+            };
+            self.line_no += 1;
+            let line = line.trim_end_matches('\r');
+            if line.is_empty() {
+                continue;
+            }
+
+            let record = Record::parse(self.next_id, line).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Line {}: {}", self.line_no, e),
+                )
+            });
+            // Ids stay positions in the log when bad lines are skipped
+            if record.is_ok() {
+                self.next_id += 1;
+            }
+            return Some(record);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    #[test]
+    fn test_load_small_file() {
+        let path = testing::log_file("small", &testing::LOG_LINES);
+        let events = Record::read_records(&path);
+        assert_eq!(events.is_ok(), true);
+    }
+
+    #[test]
+    fn test_parse() {
+        let path = testing::log_file("parse", &testing::LOG_LINES);
+        let events = Record::read_records(&path).unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[0],
+            Record {
+                date: Local.with_ymd_and_hms(2024, 5, 1, 10, 0, 0).unwrap(),
+                ..testing::record()
+            }
+        );
+        assert_eq!(events[2].id, 2);
+        assert_eq!(events[2].log_message, "Disk full\n\tat /var");
+
+        assert_eq!(
+            Record::parse(0, "01. 05. 2024 10:00:00.000\tMy PC\tabc").unwrap_err(),
+            "Bad process ID 'abc'"
+        );
+        let path = testing::log_file("bad", &["01. 05. 2024 10:00:00.000\tMy PC"]);
+        let error = Record::read_records(&path).unwrap_err();
+        assert_eq!(error.to_string(), "Line 1: Missing the process ID");
+    }
+
+    #[test]
+    fn test_stream_records() {
+        let path = testing::log_file("stream", &testing::LOG_LINES);
+        let key = |record: &Record| (record.id, record.log_message.clone());
+        let events: Vec<_> = Record::read_records(&path)
+            .unwrap()
+            .iter()
+            .map(key)
+            .collect();
+        let streamed: Vec<_> = Record::records(&path)
+            .unwrap()
+            .map(|r| key(&r.unwrap()))
+            .collect();
+        assert_eq!(streamed, events);
+
+        // Lines are parsed when asked for, a bad line only fails its own item
+        let mut lines = testing::LOG_LINES.to_vec();
+        lines.insert(1, "not a record");
+        let path = testing::log_file("stream_bad", &lines);
+        let mut records = Record::records(&path).unwrap();
+        assert_eq!(records.next().unwrap().unwrap().id, 0);
+        let error = records.next().unwrap().unwrap_err();
+        assert_eq!(error.to_string(), "Line 2: Bad date 'not a record'");
+        assert_eq!(records.next().unwrap().unwrap().id, 1);
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use crate::record::Record;
+
+/// Trigram index over `Record::log_message`.
+///
+/// Keys are built from ASCII-lowercased bytes, so the candidates returned for a
+/// needle are a superset of the case-sensitive matches, and of the case-insensitive
+/// ones only when the needle is ASCII. Other letters have case forms with different
+/// bytes, so searches ignoring their case cannot use the index. Callers always have
+/// to verify the candidates against the record.
+#[derive(Debug, Default)]
+pub struct SearchIndex {
+    trigrams: HashMap<[u8; 3], Vec<u32>>,
+}
+
+fn trigrams(text: &str) -> impl Iterator<Item = [u8; 3]> + '_ {
+    text.as_bytes().windows(3).map(|w| {
+        [
+            w[0].to_ascii_lowercase(),
+            w[1].to_ascii_lowercase(),
+            w[2].to_ascii_lowercase(),
+        ]
+    })
+}
+
+impl SearchIndex {
+    pub fn build(records: &[Record]) -> Self {
+        let mut index: HashMap<[u8; 3], Vec<u32>> = HashMap::new();
+
+        for (idx, record) in records.iter().enumerate() {
+            let idx = idx as u32;
+            for trigram in trigrams(&record.log_message) {
+                let postings = index.entry(trigram).or_default();
+                // records are visited in order, so a duplicate can only be the last entry
+                if postings.last() != Some(&idx) {
+                    postings.push(idx);
+                }
+            }
+        }
+
+        Self { trigrams: index }
+    }
+
+    /// Returns sorted indexes into the record list that may contain `needle`.
+    ///
+    /// `None` means the index cannot narrow the search (the needle is shorter
+    /// than a trigram) and the caller has to scan every record.
+    pub fn candidates(&self, needle: &str) -> Option<Vec<usize>> {
+        if needle.len() < 3 {
+            return None;
+        }
+
+        let mut postings = Vec::new();
+        for trigram in trigrams(needle) {
+            match self.trigrams.get(&trigram) {
+                Some(list) => postings.push(list),
+                None => return Some(vec![]),
+            }
+        }
+        postings.sort_by_key(|list| list.len());
+        postings.dedup();
+
+        let mut result: Vec<u32> = postings[0].clone();
+        for list in &postings[1..] {
+            result.retain(|idx| list.binary_search(idx).is_ok());
+            if result.is_empty() {
+                break;
+            }
+        }
+
+        Some(result.into_iter().map(|idx| idx as usize).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn record(id: u64, log_message: &str) -> Record {
+        Record {
+            id,
+            log_message: String::from(log_message),
+            ..testing::record()
+        }
+    }
+
+    #[test]
+    fn test_candidates() {
+        let records = vec![
+            record(0, "Connection timeout"),
+            record(1, "Retry after TIMEOUT"),
+            record(2, "All good"),
+        ];
+        let index = SearchIndex::build(&records);
+
+        assert_eq!(index.candidates("timeout"), Some(vec![0, 1]));
+        assert_eq!(index.candidates("after time"), Some(vec![1]));
+        assert_eq!(index.candidates("deadlock"), Some(vec![]));
+        assert_eq!(index.candidates("ti"), None);
+    }
+}
@@ -0,0 +1,39 @@
+//! Records for tests, shared with the tests of the viewer through the `testing` feature.
+
+use std::{fs, path::PathBuf};
+
+use chrono::{Local, TimeZone};
+
+use crate::Record;
+
+/// A record with fixed values. Tests set the fields they look at with struct update syntax,
+/// `Record { log_level: 2, ..testing::record() }`.
+pub fn record() -> Record {
+    Record {
+        id: 0,
+        date: Local.with_ymd_and_hms(2024, 5, 1, 10, 0, 0).unwrap(),
+        computer_name: String::from("My PC"),
+        process_id: 1234,
+        process_user: String::from("My User"),
+        module_name: String::from("Hello"),
+        message_id: 1,
+        log_level: 3,
+        facility: 5,
+        log_message: String::from("This is a log message 1"),
+    }
+}
+
+/// Lines of a small log, the first one is [`record`].
+pub const LOG_LINES: [&str; 4] = [
+    "01. 05. 2024 10:00:00.000\tMy PC\t1234\tMy User\tHello\t1\t3\t5\tThis is a log message 1",
+    "01. 05. 2024 10:00:30.000\tMy PC\t1234\tMy User\tNet\t2\t2\t4\tConnection lost",
+    "",
+    "01. 05. 2024 10:01:00.000\tMy PC\t1234\tMy User\tDisk\t3\t2\t4\tDisk full\\n\tat /var",
+];
+
+/// Writes `lines` to a log file in the temporary directory and returns its path.
+pub fn log_file(name: &str, lines: &[&str]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("log_reader_{}_{}.log", std::process::id(), name));
+    fs::write(&path, lines.join("\n")).unwrap();
+    path
+}
@@ -0,0 +1,214 @@
+use std::fmt;
+
+use iced::Color;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MarkColor {
+    #[default]
+    Green,
+    Yellow,
+    Red,
+    Blue,
+    Purple,
+}
+
+impl MarkColor {
+    pub const ALL: [MarkColor; 5] = [
+        MarkColor::Green,
+        MarkColor::Yellow,
+        MarkColor::Red,
+        MarkColor::Blue,
+        MarkColor::Purple,
+    ];
+
+    pub fn color(&self) -> Color {
+        match self {
+            MarkColor::Green => iced::color!(0, 255, 0),
+            MarkColor::Yellow => iced::color!(255, 220, 0),
+            MarkColor::Red => iced::color!(255, 110, 110),
+            MarkColor::Blue => iced::color!(100, 180, 255),
+            MarkColor::Purple => iced::color!(200, 140, 255),
+        }
+    }
+}
+
+impl fmt::Display for MarkColor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// A marked record with an optional note.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bookmark {
+    pub id: u64,
+    #[serde(default)]
+    pub note: String,
+    #[serde(default)]
+    pub color: MarkColor,
+}
+
+/// The marks of the log, kept sorted by record ID.
+#[derive(Debug, Clone, Default)]
+pub struct Bookmarks {
+    marks: Vec<Bookmark>,
+    /// Bumped on every change, so views depending on the marks know to refresh
+    generation: u64,
+}
+
+impl Bookmarks {
+    pub fn from_marks(mut marks: Vec<Bookmark>) -> Self {
+        marks.sort_by_key(|mark| mark.id);
+        marks.dedup_by_key(|mark| mark.id);
+        Self {
+            marks,
+            generation: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.marks.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.marks.len()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Bookmark> {
+        self.marks.iter()
+    }
+
+    pub fn get(&self, id: u64) -> Option<&Bookmark> {
+        self.find(id).ok().map(|idx| &self.marks[idx])
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.find(id).is_ok()
+    }
+
+    fn find(&self, id: u64) -> Result<usize, usize> {
+        self.marks.binary_search_by_key(&id, |mark| mark.id)
+    }
+
+    pub fn add(&mut self, id: u64, color: MarkColor) {
+        if let Err(idx) = self.find(id) {
+            self.marks.insert(
+                idx,
+                Bookmark {
+                    id,
+                    note: String::default(),
+                    color,
+                },
+            );
+            self.generation += 1;
+        }
+    }
+
+    pub fn remove(&mut self, id: u64) {
+        if let Ok(idx) = self.find(id) {
+            self.marks.remove(idx);
+            self.generation += 1;
+        }
+    }
+
+    pub fn toggle(&mut self, id: u64, color: MarkColor) {
+        if self.contains(id) {
+            self.remove(id);
+        } else {
+            self.add(id, color);
+        }
+    }
+
+    pub fn set_note(&mut self, id: u64, note: String) {
+        if let Ok(idx) = self.find(id) {
+            self.marks[idx].note = note;
+        }
+    }
+
+    pub fn set_color(&mut self, id: u64, color: MarkColor) {
+        if let Ok(idx) = self.find(id) {
+            self.marks[idx].color = color;
+            self.generation += 1;
+        }
+    }
+
+    /// Positions in `rows` of the marked records, only those of `color` if given,
+    /// in the order they are shown.
+    pub fn positions(&self, rows: &[usize], color: Option<MarkColor>) -> Vec<(usize, u64)> {
+        if self.marks.is_empty() {
+            return vec![];
+        }
+        rows.iter()
+            .enumerate()
+            .filter_map(|(pos, &row)| {
+                let mark = self.get(row as u64)?;
+                color
+                    .is_none_or(|color| mark.color == color)
+                    .then_some((pos, mark.id))
+            })
+            .collect()
+    }
+}
+
+/// The mark after `current` in `positions`, or before it going back, wrapping around.
+pub fn next_mark(
+    positions: &[(usize, u64)],
+    current: usize,
+    forward: bool,
+) -> Option<(usize, u64)> {
+    if forward {
+        positions
+            .iter()
+            .find(|&&(pos, _)| pos > current)
+            .or(positions.first())
+            .copied()
+    } else {
+        positions
+            .iter()
+            .rev()
+            .find(|&&(pos, _)| pos < current)
+            .or(positions.last())
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_marks_by_color() {
+        let mut marks = Bookmarks::default();
+        marks.add(7, MarkColor::Red);
+        marks.add(2, MarkColor::Green);
+        marks.toggle(5, MarkColor::Red);
+        marks.add(7, MarkColor::Blue);
+        marks.set_note(2, String::from("start"));
+        assert_eq!(
+            marks.iter().map(|m| m.id).collect::<Vec<_>>(),
+            vec![2, 5, 7]
+        );
+        assert_eq!(marks.get(7).unwrap().color, MarkColor::Red);
+        assert_eq!(marks.get(2).unwrap().note, "start");
+
+        // Sorted rows, record 7 is shown first
+        let rows = [7, 1, 2, 5];
+        let all = marks.positions(&rows, None);
+        assert_eq!(all, vec![(0, 7), (2, 2), (3, 5)]);
+        let red = marks.positions(&rows, Some(MarkColor::Red));
+        assert_eq!(red, vec![(0, 7), (3, 5)]);
+
+        assert_eq!(next_mark(&red, 0, true), Some((3, 5)));
+        assert_eq!(next_mark(&red, 3, true), Some((0, 7)));
+        assert_eq!(next_mark(&red, 2, false), Some((0, 7)));
+        assert_eq!(next_mark(&red, 0, false), Some((3, 5)));
+
+        marks.toggle(5, MarkColor::Red);
+        assert!(!marks.contains(5));
+    }
+}
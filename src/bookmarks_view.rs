@@ -0,0 +1,151 @@
+use iced::{
+    Element,
+    Length::Fill,
+    Theme,
+    widget::{
+        Column, Row, button, column, container, pick_list, row, scrollable, text, text_input,
+    },
+};
+use log_reader_core::DATE_FORMAT;
+
+use crate::LogReader;
+use crate::bookmarks::{Bookmark, MarkColor};
+use crate::log_message::{BookmarkMessage, LogReaderMessage, ReportMessage};
+use crate::results_view::PANEL_WIDTH;
+
+fn color_button<'a>(
+    label: &'a str,
+    color: Option<MarkColor>,
+    active: bool,
+) -> Element<'a, LogReaderMessage> {
+    button(text(label).size(12))
+        .padding([2, 6])
+        .style(move |theme: &Theme, status| {
+            let mut style = if active {
+                button::primary(theme, status)
+            } else {
+                button::secondary(theme, status)
+            };
+            if let Some(color) = color {
+                style.background = Some(iced::Background::Color(color.color()));
+                style.text_color = iced::Color::BLACK;
+                if active {
+                    style.border = style.border.width(2).color(iced::Color::BLACK);
+                }
+            }
+            style
+        })
+        .on_press(LogReaderMessage::Bookmark(BookmarkMessage::ShowColor(
+            color,
+        )))
+        .into()
+}
+
+fn bookmark_row<'a>(
+    log_reader: &'a LogReader,
+    mark: &'a Bookmark,
+) -> Element<'a, LogReaderMessage> {
+    let msg = LogReaderMessage::Bookmark;
+    let id = mark.id;
+    let label = match log_reader.events.get(id as usize) {
+        Some(record) => format!("{}  #{}", record.date.format(DATE_FORMAT), id),
+        None => format!("#{}", id),
+    };
+    let label = text(label).size(12);
+    column![
+        row![
+            pick_list(MarkColor::ALL, Some(mark.color), move |color| msg(
+                BookmarkMessage::Color(id, color)
+            ))
+            .text_size(12)
+            .width(90),
+            button(label)
+                .style(button::text)
+                .width(Fill)
+                .on_press(msg(BookmarkMessage::Jump(id))),
+            button(text("\u{2715}").size(12))
+                .style(button::danger)
+                .on_press(msg(BookmarkMessage::Delete(id))),
+        ]
+        .spacing(4)
+        .align_y(iced::Alignment::Center),
+        text_input("Note", &mark.note)
+            .on_input(move |note| msg(BookmarkMessage::Note(id, note)))
+            .size(12),
+    ]
+    .spacing(2)
+    .into()
+}
+
+pub fn view(log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
+    let msg = LogReaderMessage::Bookmark;
+
+    let colors = std::iter::once(color_button("All", None, log_reader.mark_color.is_none())).chain(
+        MarkColor::ALL
+            .iter()
+            .map(|&color| color_button("  ", Some(color), log_reader.mark_color == Some(color))),
+    );
+
+    let marks = log_reader
+        .selected_rows
+        .iter()
+        .filter(|mark| {
+            log_reader
+                .mark_color
+                .is_none_or(|color| mark.color == color)
+        })
+        .map(|mark| bookmark_row(log_reader, mark));
+
+    container(
+        column![
+            row![
+                text(format!("Bookmarks ({})", log_reader.selected_rows.len())).width(Fill),
+                button("Report").on_press_maybe(
+                    (!log_reader.selected_rows.is_empty())
+                        .then_some(LogReaderMessage::Report(ReportMessage::OpenDialog))
+                ),
+                button("Close").on_press(msg(BookmarkMessage::TogglePanel)),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+            Row::with_children(colors).spacing(4),
+            if log_reader.selected_rows.is_empty() {
+                Element::from(text("Press m or right-click a row to mark it."))
+            } else {
+                scrollable(Column::with_children(marks).spacing(8).padding([0, 8]))
+                    .height(Fill)
+                    .into()
+            },
+        ]
+        .spacing(8),
+    )
+    .padding(8)
+    .width(PANEL_WIDTH)
+    .height(Fill)
+    .style(|theme: &Theme| {
+        container::Style::default()
+            .background(theme.extended_palette().background.weak.color)
+            .border(
+                iced::Border::default()
+                    .width(1)
+                    .color(theme.palette().success),
+            )
+    })
+    .into()
+}
+
+pub fn update(log_reader: &mut LogReader, msg: BookmarkMessage) {
+    match msg {
+        BookmarkMessage::TogglePanel => log_reader.bookmarks_panel = !log_reader.bookmarks_panel,
+        BookmarkMessage::ShowColor(color) => log_reader.mark_color = color,
+        // Marks hidden by the filters are left where they are
+        BookmarkMessage::Jump(id) => {
+            if let Some(scroll_idx) = log_reader._get_row_idx_scroll_idx(id) {
+                log_reader._jump_to(scroll_idx);
+            }
+        }
+        BookmarkMessage::Note(id, note) => log_reader.selected_rows.set_note(id, note),
+        BookmarkMessage::Color(id, color) => log_reader.selected_rows.set_color(id, color),
+        BookmarkMessage::Delete(id) => log_reader.selected_rows.remove(id),
+    }
+}
@@ -0,0 +1,355 @@
+use log_reader_core::{
+    Dictionary, Field, Record,
+    filter::{self, Condition},
+};
+use std::{
+    collections::BTreeMap,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+};
+
+use crate::export::{self, ExportFormat, ExportRow};
+
+/// Like grep: some records matched, none matched, or the arguments or the log were bad.
+pub const EXIT_MATCHES: i32 = 0;
+pub const EXIT_NO_MATCHES: i32 = 1;
+pub const EXIT_ERROR: i32 = 2;
+
+const USAGE: &str = "\
+Usage: log_reader --no-gui [OPTIONS] FILE
+
+Prints the records of FILE that match all the filters.
+
+Options:
+  --filter QUERY    Conditions like 'level>=3 module~Net msg!~\"timed out\"',
+                    operators = != < <= > >= ~ (contains) !~ (does not contain)
+  --dictionary PATH Dictionary file giving names to log levels, facilities and
+                    message IDs, used by the filters and the output
+  --format FORMAT   text (default), csv, json (one object per line) or html
+  --fields LIST     Columns to print, like 'date,level,module,message'
+  --count           Print only the number of matching records
+  --stats           Print only the matching records per log level and module
+
+Exit status is 0 when records matched, 1 when none did and 2 on errors.";
+
+/// Names with their record count, the most frequent first.
+type Counts = Vec<(String, usize)>;
+
+#[derive(Debug, Default, PartialEq)]
+enum Output {
+    #[default]
+    Records,
+    Count,
+    Stats,
+}
+
+#[derive(Debug, Default)]
+struct Options {
+    file_name: String,
+    dictionary: Option<PathBuf>,
+    conditions: Vec<Condition>,
+    format: ExportFormat,
+    fields: Vec<Field>,
+    output: Output,
+}
+
+fn parse_format(name: &str) -> Result<ExportFormat, String> {
+    match name.to_ascii_lowercase().as_str() {
+        "text" | "txt" => Ok(ExportFormat::Text),
+        "csv" => Ok(ExportFormat::Csv),
+        "json" | "jsonl" => Ok(ExportFormat::JsonLines),
+        "html" => Ok(ExportFormat::Html),
+        _ => Err(format!("Unknown format '{}'", name)),
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        format: ExportFormat::Text,
+        fields: Field::ALL.to_vec(),
+        ..Options::default()
+    };
+    let mut file_name = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        // Both `--filter QUERY` and `--filter=QUERY`
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_owned())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next().cloned())
+                .ok_or_else(|| format!("Missing value for {}", flag))
+        };
+
+        match flag {
+            "--no-gui" => (),
+            "--filter" => options.conditions.extend(filter::parse_query(&value()?)?),
+            "--dictionary" => options.dictionary = Some(PathBuf::from(value()?)),
+            "--format" => options.format = parse_format(&value()?)?,
+            "--fields" => {
+                options.fields = value()?
+                    .split(',')
+                    .map(|name| {
+                        filter::parse_field(name.trim())
+                            .ok_or_else(|| format!("Unknown field '{}'", name))
+                    })
+                    .collect::<Result<_, _>>()?;
+            }
+            "--count" => options.output = Output::Count,
+            "--stats" => options.output = Output::Stats,
+            flag if flag.starts_with("--") => return Err(format!("Unknown option {}", flag)),
+            _ if file_name.is_some() => return Err(format!("Unexpected argument '{}'", arg)),
+            _ => file_name = Some(arg.clone()),
+        }
+    }
+
+    options.file_name = file_name.ok_or("No input file given")?;
+    Ok(options)
+}
+
+/// Matching records per shown log level and per module.
+fn stats(records: &[&Record], dictionary: &Dictionary) -> (Counts, Counts) {
+    let mut levels: BTreeMap<String, usize> = BTreeMap::new();
+    let mut modules: BTreeMap<String, usize> = BTreeMap::new();
+    for record in records {
+        *levels
+            .entry(dictionary.display(Field::LogLevel, record))
+            .or_default() += 1;
+        *modules.entry(record.module_name.clone()).or_default() += 1;
+    }
+    let sorted = |counts: BTreeMap<String, usize>| {
+        let mut counts: Counts = counts.into_iter().collect();
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        counts
+    };
+    (sorted(levels), sorted(modules))
+}
+
+fn write_stats(
+    out: &mut impl Write,
+    format: ExportFormat,
+    total: usize,
+    records: &[&Record],
+    dictionary: &Dictionary,
+) -> io::Result<()> {
+    let (levels, modules) = stats(records, dictionary);
+    if format == ExportFormat::JsonLines {
+        let object = |counts: &[(String, usize)]| {
+            let members: Vec<String> = counts
+                .iter()
+                .map(|(name, count)| format!("{}: {}", serde_json::json!(name), count))
+                .collect();
+            format!("{{{}}}", members.join(", "))
+        };
+        return writeln!(
+            out,
+            "{{\"records\": {}, \"matches\": {}, \"levels\": {}, \"modules\": {}}}",
+            total,
+            records.len(),
+            object(&levels),
+            object(&modules)
+        );
+    }
+
+    let width = levels
+        .iter()
+        .chain(&modules)
+        .map(|(name, _)| name.chars().count())
+        .max()
+        .unwrap_or_default()
+        .max(7);
+    writeln!(out, "{:width$}  {}", "Records", total)?;
+    writeln!(out, "{:width$}  {}", "Matches", records.len())?;
+    for (title, counts) in [("Log level", levels), ("Module", modules)] {
+        writeln!(out, "\n{}", title)?;
+        for (name, count) in counts {
+            writeln!(out, "  {:width$}  {}", name, count)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_output(
+    out: impl Write,
+    options: &Options,
+    events: &[Record],
+    records: &[&Record],
+    dictionary: &Dictionary,
+) -> io::Result<()> {
+    let mut out = BufWriter::new(out);
+    match options.output {
+        Output::Count => writeln!(out, "{}", records.len())?,
+        Output::Stats => write_stats(&mut out, options.format, events.len(), records, dictionary)?,
+        Output::Records => {
+            let rows = records.iter().map(|record| ExportRow {
+                cells: options
+                    .fields
+                    .iter()
+                    .map(|&field| dictionary.display(field, record))
+                    .collect(),
+                colors: (None, None),
+            });
+            export::write_rows(
+                &mut out,
+                options.format,
+                &options.file_name,
+                &options.fields,
+                rows,
+            )?;
+        }
+    }
+    out.flush()
+}
+
+/// Runs without a window, e.g. `log_reader --no-gui --filter 'level>=3' file.log`, writes
+/// the output to `out` and returns the exit status.
+pub fn run(args: &[String], out: impl Write) -> i32 {
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return EXIT_ERROR;
+        }
+    };
+
+    let events = match Record::read_records(&options.file_name) {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("Cannot read {}. {}", options.file_name, e);
+            return EXIT_ERROR;
+        }
+    };
+
+    // Without --dictionary the raw values are used, like the log has them
+    let dictionary = match &options.dictionary {
+        Some(path) => match Dictionary::load(path) {
+            Ok(dictionary) => dictionary,
+            Err(e) => {
+                eprintln!("Cannot read the dictionary {}. {}", path.display(), e);
+                return EXIT_ERROR;
+            }
+        },
+        None => Dictionary::default(),
+    };
+
+    // The same checks as the column filters of the table, only reporting bad values
+    let checks = match filter::compile(&options.conditions, &dictionary) {
+        Ok(checks) => checks,
+        Err(e) => {
+            eprintln!("{}", e);
+            return EXIT_ERROR;
+        }
+    };
+
+    let records: Vec<&Record> = events
+        .iter()
+        .filter(|record| filter::matches(&checks, record))
+        .collect();
+
+    match write_output(out, &options, &events, &records, &dictionary) {
+        // The reader went away, like `| head`
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => (),
+        Err(e) => {
+            eprintln!("Failed to write the records. {}", e);
+            return EXIT_ERROR;
+        }
+        Ok(()) => (),
+    }
+
+    if records.is_empty() {
+        EXIT_NO_MATCHES
+    } else {
+        EXIT_MATCHES
+    }
+}
+
+/// Release builds on Windows have no console of their own, so the output goes to the one
+/// the viewer was started from.
+#[cfg(windows)]
+pub fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // Fails without a parent console, e.g. when started from the Explorer
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log_reader_core::testing;
+
+    fn args(line: &str) -> Vec<String> {
+        line.split(' ').map(str::to_owned).collect()
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let options = parse_args(&args(
+            "--no-gui --filter level>=3 --filter=module~Net --format json --fields date,msg a.log",
+        ))
+        .unwrap();
+        assert_eq!(options.file_name, "a.log");
+        assert_eq!(options.conditions.len(), 2);
+        assert_eq!(options.format, ExportFormat::JsonLines);
+        assert_eq!(options.fields, vec![Field::Date, Field::LogMessage]);
+        assert_eq!(options.output, Output::Records);
+
+        assert!(parse_args(&args("--no-gui --count")).is_err());
+        assert!(parse_args(&args("--no-gui --format xml a.log")).is_err());
+        assert!(parse_args(&args("--no-gui --filter")).is_err());
+        assert!(parse_args(&args("--no-gui a.log b.log")).is_err());
+    }
+
+    #[test]
+    fn test_run() {
+        let log = testing::log_file("cli", &testing::LOG_LINES);
+        let dictionary = log.with_extension("json");
+        std::fs::write(&dictionary, r#"{"log_levels": {"2": "Error"}}"#).unwrap();
+
+        let run_with = |line: &str| {
+            let mut args = args(line);
+            args.push(log.display().to_string());
+            let mut out = vec![];
+            let status = run(&args, &mut out);
+            (status, String::from_utf8(out).unwrap())
+        };
+
+        assert_eq!(
+            run_with("--no-gui --filter level<=2 --fields module,level"),
+            (
+                EXIT_MATCHES,
+                String::from("Module Name  Log Level\nNet          2\nDisk         2\n")
+            )
+        );
+        assert_eq!(
+            run_with("--no-gui --filter module~Print --count"),
+            (EXIT_NO_MATCHES, String::from("0\n"))
+        );
+        assert_eq!(
+            run_with(&format!(
+                "--no-gui --dictionary {} --filter level=Error --count",
+                dictionary.display()
+            )),
+            (EXIT_MATCHES, String::from("2\n"))
+        );
+        // Without the dictionary the level has no name
+        assert_eq!(
+            run_with("--no-gui --filter level=Error --count").0,
+            EXIT_ERROR
+        );
+
+        let missing = args("--no-gui --count missing.log");
+        assert_eq!(run(&missing, &mut vec![]), EXIT_ERROR);
+    }
+}
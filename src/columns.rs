@@ -0,0 +1,221 @@
+use std::{collections::HashMap, io};
+
+use log_reader_core::Field;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+const LAYOUTS_FILE: &str = "column_layouts.json";
+pub const MIN_COLUMN_WIDTH: f32 = 40.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnSettings {
+    pub field: Field,
+    /// None fills the remaining space
+    pub width: Option<f32>,
+    pub visible: bool,
+}
+
+/// Order, widths and visibility of the table columns.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ColumnLayout {
+    pub columns: Vec<ColumnSettings>,
+}
+
+impl Default for ColumnLayout {
+    fn default() -> Self {
+        Self {
+            columns: Field::ALL
+                .into_iter()
+                .map(|field| ColumnSettings {
+                    field,
+                    width: default_width(field),
+                    visible: true,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn default_width(field: Field) -> Option<f32> {
+    match field {
+        Field::Id => Some(100.0),
+        Field::Date => Some(200.0),
+        Field::ComputerName => Some(120.0),
+        Field::ProcessId => Some(100.0),
+        Field::ProcessUser => Some(100.0),
+        Field::ModuleName => Some(120.0),
+        Field::MessageId => Some(100.0),
+        Field::LogLevel => Some(80.0),
+        Field::Facility => Some(70.0),
+        Field::LogMessage => None,
+    }
+}
+
+/// A header being dragged, either by its border to resize it or by its title to move it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnDrag {
+    Resize {
+        field: Field,
+        start_x: f32,
+        start_width: f32,
+    },
+    Move {
+        field: Field,
+        start_x: f32,
+        target: Field,
+    },
+}
+
+impl ColumnLayout {
+    pub fn visible(&self) -> impl Iterator<Item = &ColumnSettings> {
+        self.columns.iter().filter(|column| column.visible)
+    }
+
+    /// The next visible column after (or before) `field` in the shown order, `field` itself
+    /// when it is the last (or first) one.
+    pub fn step(&self, field: Field, forward: bool) -> Field {
+        let pos = self.columns.iter().position(|c| c.field == field);
+        let (before, after) = self.columns.split_at(pos.map_or(0, |pos| pos + 1));
+        let next = if forward {
+            after.iter().find(|c| c.visible)
+        } else {
+            before.iter().rev().skip(1).find(|c| c.visible)
+        };
+        next.map_or_else(|| self.shown(field), |c| c.field)
+    }
+
+    /// `field` when it is visible, else the visible column next to it.
+    pub fn shown(&self, field: Field) -> Field {
+        let pos = self
+            .columns
+            .iter()
+            .position(|c| c.field == field)
+            .unwrap_or(0);
+        let (before, after) = self.columns.split_at(pos);
+        after
+            .iter()
+            .find(|c| c.visible)
+            .or_else(|| before.iter().rev().find(|c| c.visible))
+            .map_or(field, |c| c.field)
+    }
+
+    fn get_mut(&mut self, field: Field) -> Option<&mut ColumnSettings> {
+        self.columns.iter_mut().find(|column| column.field == field)
+    }
+
+    pub fn width(&self, field: Field) -> Option<f32> {
+        self.columns
+            .iter()
+            .find(|column| column.field == field)
+            .and_then(|column| column.width)
+    }
+
+    pub fn resize(&mut self, field: Field, width: f32) {
+        if let Some(column) = self.get_mut(field)
+            && column.width.is_some()
+        {
+            column.width = Some(width.max(MIN_COLUMN_WIDTH));
+        }
+    }
+
+    pub fn set_visible(&mut self, field: Field, visible: bool) {
+        if let Some(column) = self.get_mut(field) {
+            column.visible = visible;
+        }
+    }
+
+    /// Moves the column of `field` to the place of the column of `target`.
+    pub fn move_column(&mut self, field: Field, target: Field) {
+        let from = self.columns.iter().position(|c| c.field == field);
+        let to = self.columns.iter().position(|c| c.field == target);
+        if let (Some(from), Some(to)) = (from, to) {
+            let column = self.columns.remove(from);
+            self.columns.insert(to, column);
+        }
+    }
+
+    /// Drops unknown or repeated columns and adds missing ones, e.g. from an older layout file.
+    fn normalize(&mut self) {
+        let mut seen = vec![];
+        self.columns.retain(|column| {
+            let new = !seen.contains(&column.field);
+            seen.push(column.field);
+            new
+        });
+        for column in ColumnLayout::default().columns {
+            if !seen.contains(&column.field) {
+                self.columns.push(column);
+            }
+        }
+    }
+
+    /// The layout stored for a log format, or the default one.
+    pub fn load(format: &str) -> Self {
+        let mut layouts: HashMap<String, ColumnLayout> = config::load(LAYOUTS_FILE);
+        let mut layout = layouts.remove(format).unwrap_or_default();
+        layout.normalize();
+        layout
+    }
+
+    pub fn save(&self, format: &str) -> io::Result<()> {
+        let mut layouts: HashMap<String, ColumnLayout> = config::load(LAYOUTS_FILE);
+        layouts.insert(format.to_owned(), self.clone());
+        config::save(LAYOUTS_FILE, &layouts)
+    }
+}
+
+/// Layouts are remembered per log format, told apart by the file extension.
+pub fn log_format(file_name: &str) -> String {
+    std::path::Path::new(file_name)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_else(|| String::from("default"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layout_edits() {
+        let mut layout = ColumnLayout::default();
+        layout.move_column(Field::LogMessage, Field::Date);
+        layout.set_visible(Field::ComputerName, false);
+        layout.resize(Field::Date, 10.0);
+        layout.resize(Field::LogMessage, 500.0);
+
+        let visible: Vec<Field> = layout.visible().map(|c| c.field).collect();
+        assert_eq!(&visible[..3], &[Field::Id, Field::LogMessage, Field::Date]);
+        assert!(!visible.contains(&Field::ComputerName));
+        assert_eq!(layout.width(Field::Date), Some(MIN_COLUMN_WIDTH));
+        assert_eq!(layout.width(Field::LogMessage), None);
+
+        layout.columns.remove(0);
+        layout.columns.push(layout.columns[0].clone());
+        layout.normalize();
+        assert_eq!(layout.columns.len(), Field::ALL.len());
+        assert_eq!(layout.columns.last().unwrap().field, Field::Id);
+    }
+
+    #[test]
+    fn test_step() {
+        let mut layout = ColumnLayout::default();
+        layout.move_column(Field::LogMessage, Field::Date);
+        layout.set_visible(Field::Date, false);
+        layout.set_visible(Field::Facility, false);
+
+        // Id, LogMessage, (Date), ComputerName, ... LogLevel, (Facility)
+        assert_eq!(layout.step(Field::Id, true), Field::LogMessage);
+        assert_eq!(layout.step(Field::LogMessage, true), Field::ComputerName);
+        assert_eq!(layout.step(Field::ComputerName, false), Field::LogMessage);
+        assert_eq!(layout.step(Field::Id, false), Field::Id);
+        assert_eq!(layout.step(Field::LogLevel, true), Field::LogLevel);
+
+        assert_eq!(layout.shown(Field::Date), Field::ComputerName);
+        assert_eq!(layout.shown(Field::Facility), Field::LogLevel);
+        assert_eq!(layout.step(Field::Date, false), Field::LogMessage);
+        assert_eq!(layout.shown(Field::Id), Field::Id);
+    }
+}
@@ -0,0 +1,42 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Serialize, de::DeserializeOwned};
+
+const APP_DIR: &str = "log_reader";
+
+/// Path of `file_name` inside the user's config directory for this application.
+pub fn config_path(file_name: &str) -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join(APP_DIR).join(file_name))
+}
+
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> io::Result<T> {
+    let data = fs::read(path)?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_vec_pretty(value)?)
+}
+
+/// Loads a config file, a missing or unreadable file gives the default value.
+pub fn load<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    config_path(file_name)
+        .and_then(|path| read_json(&path).ok())
+        .unwrap_or_default()
+}
+
+pub fn save<T: Serialize>(file_name: &str, value: &T) -> io::Result<()> {
+    match config_path(file_name) {
+        Some(path) => write_json(&path, value),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "No config directory available",
+        )),
+    }
+}
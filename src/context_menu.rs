@@ -0,0 +1,253 @@
+use chrono::TimeDelta;
+use iced::{
+    Element,
+    Length::Fill,
+    Task, Theme,
+    widget::{Column, button, container, mouse_area, rule, stack, text},
+};
+use log_reader_core::{Field, FilterMode, FilterValues};
+
+use crate::LogReader;
+use crate::log_message::{ContextMenuMessage, LogReaderMessage};
+use crate::sort::SortKey;
+use crate::table_view;
+
+const MENU_WIDTH: f32 = 220.0;
+const ITEM_HEIGHT: f32 = 30.0;
+// Records logged this long before and after the clicked one are shown around it
+const SURROUNDING_TIME: TimeDelta = TimeDelta::minutes(1);
+
+/// What was right-clicked and where the menu opens, in window coordinates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContextMenu {
+    pub target: MenuTarget,
+    pub x: f32,
+    pub y: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuTarget {
+    Row(usize, Field),
+    Header(Field),
+}
+
+enum Item {
+    Action(&'static str, ContextMenuMessage),
+    // An option of a group, ticked when chosen
+    Choice(&'static str, bool, ContextMenuMessage),
+    Separator,
+}
+
+fn items(target: MenuTarget, log_reader: &LogReader) -> Vec<Item> {
+    use ContextMenuMessage::*;
+
+    match target {
+        MenuTarget::Row(event_idx, _) => {
+            let marked = log_reader.selected_rows.contains(event_idx as u64);
+            vec![
+                Item::Action(if marked { "Unmark" } else { "Mark" }, Mark),
+                Item::Action("Show Details", Details),
+                Item::Action("Copy", Copy),
+                Item::Separator,
+                Item::Action("Filter by Value", FilterByValue),
+                Item::Action("Exclude Value", ExcludeValue),
+                Item::Separator,
+                Item::Action("Show Surrounding Time", ShowSurrounding),
+            ]
+        }
+        MenuTarget::Header(field) => {
+            let mut items = vec![
+                Item::Action("Sort Ascending", Sort(false)),
+                Item::Action("Sort Descending", Sort(true)),
+            ];
+            if !log_reader.sort_keys.is_empty() {
+                items.push(Item::Action("Clear Sort", ClearSort));
+            }
+            items.push(Item::Separator);
+            // The date filter is always a range
+            if field != Field::Date {
+                let mode = log_reader.filters.mode(field);
+                items.extend(FilterMode::ALL.into_iter().map(|choice| {
+                    let label = match choice {
+                        FilterMode::Contains => "Filter: Contains",
+                        FilterMode::Equals => "Filter: Equals",
+                        FilterMode::Excludes => "Filter: Excludes",
+                    };
+                    Item::Choice(label, choice == mode, SetFilterMode(choice))
+                }));
+            }
+            let date_range = field == Field::Date && !log_reader.filters.date_before.is_empty();
+            let kept = log_reader.filters.kept.iter().any(|(f, _)| *f == field);
+            if !log_reader.filters.get(field).is_empty() || date_range || kept {
+                items.push(Item::Action("Clear Filter", ClearFilter));
+            }
+            if log_reader.filters.excluded.iter().any(|(f, _)| *f == field) {
+                items.push(Item::Action("Clear Exclusions", ClearExclusions));
+            }
+            if log_reader.filters != FilterValues::default() {
+                items.push(Item::Action("Clear All Filters", ClearAllFilters));
+            }
+            items.push(Item::Separator);
+            items.push(Item::Action("Hide Column", Hide));
+            items.push(Item::Action("Columns...", Columns));
+            items
+        }
+    }
+}
+
+pub fn view<'a>(log_reader: &'a LogReader, menu: &ContextMenu) -> Element<'a, LogReaderMessage> {
+    let msg = LogReaderMessage::ContextMenu;
+    let items = items(menu.target, log_reader);
+
+    // Keep the whole menu inside the window
+    let height = items.len() as f32 * ITEM_HEIGHT;
+    let x = menu.x.min(log_reader.window_width - MENU_WIDTH).max(0.0);
+    let y = menu.y.min(log_reader.window_height - height).max(0.0);
+
+    let entries = items.into_iter().map(|item| match item {
+        Item::Action(label, action) => button(text(label))
+            .width(Fill)
+            .style(button::text)
+            .on_press(msg(action))
+            .into(),
+        Item::Choice(label, chosen, action) => button(text(format!(
+            "{} {}",
+            if chosen { "✓" } else { "  " },
+            label
+        )))
+        .width(Fill)
+        .style(button::text)
+        .on_press(msg(action))
+        .into(),
+        Item::Separator => rule::horizontal(1).into(),
+    });
+
+    let menu = container(Column::with_children(entries).width(MENU_WIDTH))
+        .padding(4)
+        .style(|theme: &Theme| {
+            container::Style::default()
+                .background(theme.palette().background)
+                .border(
+                    iced::Border::default()
+                        .width(1)
+                        .color(theme.extended_palette().background.strong.color)
+                        .rounded(4),
+                )
+        });
+
+    // A click anywhere else closes the menu
+    stack![
+        mouse_area(container(iced::widget::space()).width(Fill).height(Fill))
+            .on_press(msg(ContextMenuMessage::Close))
+            .on_right_press(msg(ContextMenuMessage::Close)),
+        container(menu).padding(iced::Padding::default().left(x).top(y)),
+    ]
+    .into()
+}
+
+/// Shows the records logged shortly before and after the record at `event_idx`, on top of
+/// the other filters, and moves the highlight to it.
+fn show_surrounding(log_reader: &mut LogReader, event_idx: usize) {
+    let Some(date) = log_reader.events.get(event_idx).map(|record| record.date) else {
+        return;
+    };
+    log_reader.history.push(log_reader._history_state());
+    log_reader.filters.date = (date - SURROUNDING_TIME).to_rfc3339();
+    log_reader.filters.date_before = (date + SURROUNDING_TIME).to_rfc3339();
+    table_view::apply_filter(log_reader);
+
+    if let Some(pos) = log_reader
+        .events_filtered
+        .iter()
+        .position(|&idx| idx == event_idx)
+    {
+        log_reader._scroll_to(pos as u64);
+    }
+}
+
+pub fn update(log_reader: &mut LogReader, msg: ContextMenuMessage) -> Task<LogReaderMessage> {
+    if let ContextMenuMessage::Open(target) = msg {
+        if let MenuTarget::Row(event_idx, field) = target
+            && let Some(pos) = log_reader
+                .events_filtered
+                .iter()
+                .position(|&idx| idx == event_idx)
+        {
+            log_reader.highlighted_scroll_offset = pos;
+            log_reader.highlighted_column = field;
+        }
+        log_reader.context_menu = Some(ContextMenu {
+            target,
+            x: log_reader._mouse_x,
+            y: log_reader._mouse_y + log_reader._table_top() + log_reader.header_height,
+        });
+        return Task::none();
+    }
+
+    let Some(menu) = log_reader.context_menu.take() else {
+        return Task::none();
+    };
+
+    match (menu.target, msg) {
+        (MenuTarget::Row(event_idx, _), ContextMenuMessage::Mark) => {
+            log_reader._switch_mark(event_idx as u64);
+        }
+        (MenuTarget::Row(event_idx, _), ContextMenuMessage::Details) => {
+            log_reader._open_detail(event_idx);
+        }
+        (MenuTarget::Row(..), ContextMenuMessage::Copy) => {
+            return table_view::copy_rows(log_reader);
+        }
+        (MenuTarget::Row(event_idx, field), ContextMenuMessage::FilterByValue) => {
+            table_view::filter_by_value(log_reader, event_idx, field, false);
+        }
+        (MenuTarget::Row(event_idx, field), ContextMenuMessage::ExcludeValue) => {
+            table_view::filter_by_value(log_reader, event_idx, field, true);
+        }
+        (MenuTarget::Row(event_idx, _), ContextMenuMessage::ShowSurrounding) => {
+            show_surrounding(log_reader, event_idx);
+        }
+        (MenuTarget::Header(field), ContextMenuMessage::Sort(descending)) => {
+            log_reader.sort_keys = vec![SortKey { field, descending }];
+            table_view::resort(log_reader);
+        }
+        (MenuTarget::Header(_), ContextMenuMessage::ClearSort) => {
+            log_reader.sort_keys.clear();
+            table_view::resort(log_reader);
+        }
+        (MenuTarget::Header(field), ContextMenuMessage::SetFilterMode(mode)) => {
+            log_reader.history.push(log_reader._history_state());
+            log_reader.filters.set_mode(field, mode);
+            table_view::apply_filter(log_reader);
+        }
+        (MenuTarget::Header(field), ContextMenuMessage::ClearFilter) => {
+            log_reader.history.push(log_reader._history_state());
+            log_reader.filters.get_mut(field).clear();
+            log_reader.filters.kept.retain(|(f, _)| *f != field);
+            if field == Field::Date {
+                log_reader.filters.date_before.clear();
+            }
+            table_view::apply_filter(log_reader);
+        }
+        (MenuTarget::Header(field), ContextMenuMessage::ClearExclusions) => {
+            log_reader.history.push(log_reader._history_state());
+            log_reader.filters.excluded.retain(|(f, _)| *f != field);
+            table_view::apply_filter(log_reader);
+        }
+        (MenuTarget::Header(_), ContextMenuMessage::ClearAllFilters) => {
+            log_reader.history.push(log_reader._history_state());
+            log_reader.filters = FilterValues::default();
+            table_view::apply_filter(log_reader);
+        }
+        (MenuTarget::Header(field), ContextMenuMessage::Hide) => {
+            log_reader.columns.set_visible(field, false);
+            log_reader.highlighted_column = log_reader.columns.shown(log_reader.highlighted_column);
+            table_view::save_columns(log_reader);
+        }
+        (MenuTarget::Header(_), ContextMenuMessage::Columns) => {
+            log_reader.column_menu = true;
+        }
+        _ => (),
+    }
+    Task::none()
+}
@@ -0,0 +1,116 @@
+use std::fmt;
+
+use log_reader_core::Field;
+
+/// How rows are put on the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CopyFormat {
+    /// Tab separated, pastes into spreadsheets
+    #[default]
+    Tsv,
+    Markdown,
+    Json,
+}
+
+impl CopyFormat {
+    pub const ALL: [CopyFormat; 3] = [CopyFormat::Tsv, CopyFormat::Markdown, CopyFormat::Json];
+}
+
+impl fmt::Display for CopyFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CopyFormat::Tsv => "Tab separated",
+            CopyFormat::Markdown => "Markdown table",
+            CopyFormat::Json => "JSON",
+        })
+    }
+}
+
+/// Formats rows of cell values, one value per field in `fields`.
+pub fn format_rows(format: CopyFormat, fields: &[Field], rows: &[Vec<String>]) -> String {
+    match format {
+        CopyFormat::Tsv => rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|value| value.replace("\r\n", " ").replace(['\t', '\r', '\n'], " "))
+                    .collect::<Vec<_>>()
+                    .join("\t")
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        CopyFormat::Markdown => {
+            let line = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+            let header = line(fields.iter().map(|f| f.name().to_owned()).collect());
+            let separator = line(fields.iter().map(|_| String::from("---")).collect());
+            let rows = rows.iter().map(|row| {
+                line(
+                    row.iter()
+                        .map(|value| {
+                            value
+                                .replace('|', "\\|")
+                                .replace("\r\n", "<br>")
+                                .replace(['\r', '\n'], "<br>")
+                        })
+                        .collect(),
+                )
+            });
+            [header, separator]
+                .into_iter()
+                .chain(rows)
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        CopyFormat::Json => {
+            let objects = rows
+                .iter()
+                .map(|row| format!("  {}", json_object(fields, row)));
+            format!("[\n{}\n]", objects.collect::<Vec<_>>().join(",\n"))
+        }
+    }
+}
+
+/// A JSON object of the row, written by hand so the keys keep the order of the columns.
+pub fn json_object(fields: &[Field], row: &[String]) -> String {
+    let members = fields
+        .iter()
+        .zip(row)
+        .map(|(field, value)| format!("{}: {}", json_string(field.name()), json_string(value)));
+    format!("{{{}}}", members.collect::<Vec<_>>().join(", "))
+}
+
+fn json_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_formats() {
+        let fields = [Field::Id, Field::LogMessage];
+        let rows = vec![
+            vec![String::from("1"), String::from("a | b")],
+            vec![String::from("2"), String::from("say \"hi\"\tnow")],
+            vec![String::from("3"), String::from("first\r\nsecond\rthird")],
+        ];
+
+        assert_eq!(
+            format_rows(CopyFormat::Tsv, &fields, &rows),
+            "1\ta | b\n2\tsay \"hi\" now\n3\tfirst second third"
+        );
+
+        let markdown = format_rows(CopyFormat::Markdown, &fields, &rows);
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[1], "| --- | --- |");
+        assert_eq!(lines[2], "| 1 | a \\| b |");
+        assert_eq!(lines[4], "| 3 | first<br>second<br>third |");
+
+        let json = format_rows(CopyFormat::Json, &fields, &rows);
+        let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed[1][Field::LogMessage.name()], "say \"hi\"\tnow");
+        assert!(json.find(Field::Id.name()) < json.find(Field::LogMessage.name()));
+    }
+}
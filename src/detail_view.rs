@@ -3,13 +3,78 @@ use iced::{
     Length::Fill,
     Task, Theme,
     advanced::graphics::core::keyboard,
-    widget::{column, container, row, scrollable, text_editor, text_input},
+    widget::{column, container, row, text, text_editor, text_input},
 };
 
+use iced::widget::text_editor::{Cursor, Position};
 use iced::widget::text_input::Status;
+use log_reader_core::{Field, Record};
 
+use crate::highlight::{self, HighlightSettings, MatchKind, MessageHighlighter};
 use crate::{LogReader, ViewDetail, log_message::LogReaderMessage};
 
+impl ViewDetail {
+    pub fn new(record: Record, highlight: HighlightSettings) -> Self {
+        let matcher = highlight.matcher();
+        let mut matches: Vec<(usize, std::ops::Range<usize>)> = vec![];
+        for (line, txt) in record.log_message.lines().enumerate() {
+            let mut last_kind = None;
+            for (range, kind) in matcher.ranges(Field::LogMessage, txt) {
+                // Capture groups and the parts around them are cycled as one match
+                let same_match = last_kind.is_some_and(|last| {
+                    (last == MatchKind::Group || kind == MatchKind::Group)
+                        && last != MatchKind::Filter
+                        && kind != MatchKind::Filter
+                });
+                match matches.last_mut() {
+                    Some((_, last)) if same_match && last.end == range.start => {
+                        last.end = range.end
+                    }
+                    _ => matches.push((line, range)),
+                }
+                last_kind = Some(kind);
+            }
+        }
+
+        Self {
+            content: text_editor::Content::with_text(&record.log_message),
+            record,
+            highlight,
+            matches,
+            current_match: None,
+        }
+    }
+
+    /// Selects the next (or previous) match in the message, wrapping around.
+    fn select_match(&mut self, forward: bool) {
+        let count = self.matches.len();
+        if count == 0 {
+            return;
+        }
+
+        let idx = match (self.current_match, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(idx), true) => (idx + 1) % count,
+            (Some(idx), false) => (idx + count - 1) % count,
+        };
+        self.current_match = Some(idx);
+
+        // The columns of iced's text editor are byte offsets into the line, like the ranges
+        let (line, range) = self.matches[idx].clone();
+        self.content.move_to(Cursor {
+            position: Position {
+                line,
+                column: range.end,
+            },
+            selection: Some(Position {
+                line,
+                column: range.start,
+            }),
+        });
+    }
+}
+
 fn field_mod<'a>(title: &'a str, value: &str) -> Element<'a, LogReaderMessage> {
     container(
         column![
@@ -32,8 +97,21 @@ fn field_mod<'a>(title: &'a str, value: &str) -> Element<'a, LogReaderMessage> {
 
 pub fn view(log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
     // text_input("", &log_reader.view_detail.as_ref().unwrap().record.log_message).into()
-    let ViewDetail { record, content } = log_reader.view_detail.as_ref().unwrap();
+    let ViewDetail {
+        record,
+        content,
+        highlight,
+        matches,
+        current_match,
+    } = log_reader.view_detail.as_ref().unwrap();
     let dt = record.date.format("%d/%m/%Y %H:%M:%S%.3f").to_string();
+    let dictionary = &log_reader.dictionary;
+    let named = |field: Field| {
+        dictionary
+            .describe(field, record)
+            .and_then(|txt| txt.lines().next().map(str::to_owned))
+            .unwrap_or_else(|| field.value(record))
+    };
 
     const FIELD_SPACING_AMOUNT: u32 = 10;
 
@@ -59,18 +137,37 @@ pub fn view(log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
             field_mod("Module Name", &record.module_name),
             column![
                 row!(
-                    field_mod("Message ID", &record.message_id.to_string()),
-                    field_mod("Log Level", &record.log_level.to_string()),
+                    field_mod("Message ID", &named(Field::MessageId)),
+                    field_mod("Log Level", &named(Field::LogLevel)),
                 )
                 .spacing(FIELD_SPACING_AMOUNT)
             ]
             .spacing(FIELD_SPACING_AMOUNT)
         )
         .spacing(FIELD_SPACING_AMOUNT),
-        field_mod("Facility", &record.facility.to_string()),
-        container(scrollable(
-            text_editor(&content).on_action(LogReaderMessage::TextEditorEdit)
-        ))
+        row!(
+            field_mod("Facility", &named(Field::Facility)),
+            container(text(match (matches.len(), current_match) {
+                (0, _) => String::default(),
+                (count, None) => format!("{} matches, F3 / Shift + F3 to cycle", count),
+                (count, Some(idx)) => format!("Match {} of {}", idx + 1, count),
+            }))
+            .align_bottom(Fill)
+        )
+        .spacing(FIELD_SPACING_AMOUNT),
+        text(
+            dictionary
+                .message_ids
+                .get(&record.message_id)
+                .map(|t| format!("{}\n{}", t.template, t.description))
+                .unwrap_or_default()
+        ),
+        container(
+            text_editor(content)
+                .on_action(LogReaderMessage::TextEditorEdit)
+                .highlight_with::<MessageHighlighter>(highlight.clone(), highlight::detail_format)
+                .height(Fill)
+        )
         .width(Fill)
         .height(Fill)
     ]
@@ -92,20 +189,62 @@ pub fn update(log_reader: &mut LogReader, msg: LogReaderMessage) -> Task<LogRead
                     .perform(action);
             }
         },
-        LogReaderMessage::Event(event) => match event {
-            iced::Event::Keyboard(keyboard_event) => match keyboard_event {
-                iced::keyboard::Event::KeyPressed { key, .. } => {
-                    if key == keyboard::Key::Named(keyboard::key::Named::Escape) {
+        LogReaderMessage::Event(event) => {
+            if let iced::Event::Keyboard(keyboard_event) = event
+                && let iced::keyboard::Event::KeyPressed { key, modifiers, .. } = keyboard_event
+            {
+                match key {
+                    keyboard::Key::Named(keyboard::key::Named::Escape) => {
                         log_reader.view_detail = None;
                     }
+                    keyboard::Key::Named(keyboard::key::Named::F3) => {
+                        if let Some(detail) = log_reader.view_detail.as_mut() {
+                            detail.select_match(!modifiers.shift());
+                        }
+                    }
+                    _ => (),
                 }
-                _ => (),
-            },
-            _ => (),
-        },
+            }
+        }
 
         _ => (),
     }
 
     Task::none()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::SearchOptions;
+    use log_reader_core::testing;
+
+    #[test]
+    fn test_select_match() {
+        let record = Record {
+            log_message: String::from("Größe: Datei über 2 GB\nnoch über"),
+            ..testing::record()
+        };
+        let settings = HighlightSettings {
+            search_text: String::from(r"(\d+) GB|noch"),
+            search_options: SearchOptions {
+                regex: true,
+                ..SearchOptions::default()
+            },
+            filter_terms: vec![(Field::LogMessage, String::from("über"))],
+        };
+        let mut detail = ViewDetail::new(record, settings);
+
+        // The capture group and the text around it are one match
+        let selections: Vec<Option<String>> = (0..4)
+            .map(|_| {
+                detail.select_match(true);
+                detail.content.selection()
+            })
+            .collect();
+        assert_eq!(
+            selections,
+            ["über", "2 GB", "noch", "über"].map(|txt| Some(String::from(txt)))
+        );
+    }
+}
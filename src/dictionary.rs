@@ -0,0 +1,33 @@
+use std::{io, path::PathBuf};
+
+use log_reader_core::Dictionary;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+const SETTINGS_FILE: &str = "dictionary.json";
+
+/// Remembers which dictionary file was loaded last.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct DictionarySettings {
+    path: Option<PathBuf>,
+}
+
+/// The dictionary loaded last time, or an empty one.
+pub fn load_last() -> Dictionary {
+    let settings: DictionarySettings = config::load(SETTINGS_FILE);
+    settings
+        .path
+        .and_then(|path| Dictionary::load(&path).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_last(dictionary: &Dictionary) -> io::Result<()> {
+    config::save(
+        SETTINGS_FILE,
+        &DictionarySettings {
+            path: dictionary.path.clone(),
+        },
+    )
+}
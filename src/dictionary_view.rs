@@ -0,0 +1,142 @@
+use std::path::Path;
+
+use iced::{
+    Element,
+    Length::Fill,
+    Task, Theme,
+    widget::{button, column, container, row, text, text_input},
+};
+use log_reader_core::Dictionary;
+
+use crate::dictionary;
+use crate::log_message::{DictionaryMessage, LogReaderMessage};
+use crate::{LogReader, table_view};
+
+#[derive(Debug, Default)]
+pub struct DictionaryDialog {
+    path: String,
+    status: String,
+}
+
+fn summary(dictionary: &Dictionary) -> String {
+    match &dictionary.path {
+        Some(path) => format!(
+            "{} ({}): {} log levels, {} facilities, {} message IDs",
+            dictionary.product,
+            path.display(),
+            dictionary.log_levels.len(),
+            dictionary.facilities.len(),
+            dictionary.message_ids.len()
+        ),
+        None => String::from("No dictionary loaded, raw values are shown."),
+    }
+}
+
+pub fn view(log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
+    let dialog = log_reader.dictionary_dialog.as_ref().unwrap();
+    let msg = LogReaderMessage::Dictionary;
+
+    container(
+        container(
+            column![
+                text("Dictionary"),
+                text(summary(&log_reader.dictionary)),
+                row![
+                    text_input("Dictionary file path", &dialog.path)
+                        .on_input(move |txt| msg(DictionaryMessage::PathInput(txt))),
+                    button("Load").on_press(msg(DictionaryMessage::Load)),
+                    button("Unload").on_press_maybe(
+                        log_reader
+                            .dictionary
+                            .path
+                            .is_some()
+                            .then_some(msg(DictionaryMessage::Unload))
+                    ),
+                ]
+                .spacing(8),
+                row![
+                    text(&dialog.status).width(Fill),
+                    button("Close").on_press(msg(DictionaryMessage::CloseDialog)),
+                ]
+                .align_y(iced::Alignment::Center),
+            ]
+            .padding(16)
+            .spacing(16),
+        )
+        .width(700)
+        .style(|theme: &Theme| {
+            container::Style::default()
+                .background(theme.palette().background)
+                .border(
+                    iced::Border::default()
+                        .width(2)
+                        .color(theme.palette().success),
+                )
+        }),
+    )
+    .center(Fill)
+    .into()
+}
+
+/// Swaps the dictionary, the filters are applied again as names may resolve differently.
+fn set_dictionary(log_reader: &mut LogReader, dictionary: Dictionary) -> String {
+    log_reader.dictionary = dictionary;
+    table_view::apply_filter(log_reader);
+    match dictionary::save_last(&log_reader.dictionary) {
+        Ok(()) => String::default(),
+        Err(e) => format!("Failed to remember the dictionary. {}", e),
+    }
+}
+
+pub fn update(log_reader: &mut LogReader, msg: LogReaderMessage) -> Task<LogReaderMessage> {
+    let msg = match msg {
+        LogReaderMessage::Dictionary(msg) => msg,
+        LogReaderMessage::Event(iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+            key: iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape),
+            ..
+        })) => DictionaryMessage::CloseDialog,
+        _ => return Task::none(),
+    };
+
+    match msg {
+        DictionaryMessage::OpenDialog => {
+            log_reader.dictionary_dialog = Some(DictionaryDialog {
+                path: log_reader
+                    .dictionary
+                    .path
+                    .as_ref()
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default(),
+                ..DictionaryDialog::default()
+            });
+        }
+        DictionaryMessage::CloseDialog => {
+            log_reader.dictionary_dialog = None;
+        }
+        DictionaryMessage::PathInput(txt) => {
+            if let Some(dialog) = log_reader.dictionary_dialog.as_mut() {
+                dialog.path = txt;
+            }
+        }
+        DictionaryMessage::Load => {
+            let Some(dialog) = log_reader.dictionary_dialog.as_ref() else {
+                return Task::none();
+            };
+            let status = match Dictionary::load(Path::new(&dialog.path)) {
+                Ok(dictionary) => set_dictionary(log_reader, dictionary),
+                Err(e) => format!("Load failed. {}", e),
+            };
+            if let Some(dialog) = log_reader.dictionary_dialog.as_mut() {
+                dialog.status = status;
+            }
+        }
+        DictionaryMessage::Unload => {
+            let status = set_dictionary(log_reader, Dictionary::default());
+            if let Some(dialog) = log_reader.dictionary_dialog.as_mut() {
+                dialog.status = status;
+            }
+        }
+    }
+
+    Task::none()
+}
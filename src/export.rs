@@ -0,0 +1,245 @@
+use std::{
+    fmt,
+    io::{self, Write},
+};
+
+use iced::Color;
+use log_reader_core::Field;
+
+use crate::copy::json_object;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    JsonLines,
+    Text,
+    Html,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 4] = [
+        ExportFormat::Csv,
+        ExportFormat::JsonLines,
+        ExportFormat::Text,
+        ExportFormat::Html,
+    ];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::JsonLines => "jsonl",
+            ExportFormat::Text => "txt",
+            ExportFormat::Html => "html",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::JsonLines => "JSON Lines",
+            ExportFormat::Text => "Plain text",
+            ExportFormat::Html => "HTML report",
+        })
+    }
+}
+
+/// Which of the shown rows get exported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportScope {
+    #[default]
+    Shown,
+    Marked,
+    Selected,
+}
+
+impl ExportScope {
+    pub const ALL: [ExportScope; 3] = [
+        ExportScope::Shown,
+        ExportScope::Marked,
+        ExportScope::Selected,
+    ];
+}
+
+impl fmt::Display for ExportScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ExportScope::Shown => "All shown rows",
+            ExportScope::Marked => "Marked rows",
+            ExportScope::Selected => "Selected rows",
+        })
+    }
+}
+
+/// Cell values of one record and its severity background and text colors.
+pub struct ExportRow {
+    pub cells: Vec<String>,
+    pub colors: (Option<Color>, Option<Color>),
+}
+
+/// Writes the rows in `format`, the cells being values of `fields`. Returns the row count.
+pub fn write_rows(
+    out: &mut impl Write,
+    format: ExportFormat,
+    title: &str,
+    fields: &[Field],
+    rows: impl Iterator<Item = ExportRow>,
+) -> io::Result<usize> {
+    let mut count = 0;
+    match format {
+        ExportFormat::Csv => {
+            let header: Vec<String> = fields.iter().map(|f| f.name().to_owned()).collect();
+            writeln!(out, "{}", csv_line(&header))?;
+            for row in rows {
+                writeln!(out, "{}", csv_line(&row.cells))?;
+                count += 1;
+            }
+        }
+        ExportFormat::JsonLines => {
+            for row in rows {
+                writeln!(out, "{}", json_object(fields, &row.cells))?;
+                count += 1;
+            }
+        }
+        // Columns are padded to their widest value, like the table
+        ExportFormat::Text => {
+            let header: Vec<String> = fields.iter().map(|f| f.name().to_owned()).collect();
+            let rows: Vec<Vec<String>> = std::iter::once(header)
+                .chain(rows.map(|row| row.cells))
+                .map(|cells| cells.iter().map(|c| c.replace(['\r', '\n'], " ")).collect())
+                .collect();
+            let mut widths = vec![0; fields.len()];
+            for cells in &rows {
+                for (width, cell) in widths.iter_mut().zip(cells) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+            for cells in &rows {
+                let line: Vec<String> = cells
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, &width)| format!("{:width$}", cell))
+                    .collect();
+                writeln!(out, "{}", line.join("  ").trim_end())?;
+            }
+            count = rows.len() - 1;
+        }
+        ExportFormat::Html => {
+            writeln!(
+                out,
+                "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n\
+                 <style>\nbody {{ font-family: sans-serif; }}\n\
+                 table {{ border-collapse: collapse; font-family: monospace; }}\n\
+                 th, td {{ border: 1px solid #ccc; padding: 2px 6px; text-align: left; \
+                 vertical-align: top; white-space: pre-wrap; }}\n\
+                 th {{ background: #eee; }}\n</style>\n</head>\n<body>\n<h1>{0}</h1>\n<table>",
+                html_escape(title)
+            )?;
+            let header: String = fields
+                .iter()
+                .map(|f| format!("<th>{}</th>", html_escape(f.name())))
+                .collect();
+            writeln!(out, "<tr>{}</tr>", header)?;
+            for row in rows {
+                let style = match row.colors {
+                    (None, None) => String::default(),
+                    (background, text) => format!(
+                        " style=\"{}{}\"",
+                        background.map_or(String::default(), |c| format!(
+                            "background: {};",
+                            css_color(c)
+                        )),
+                        text.map_or(String::default(), |c| format!(" color: {};", css_color(c))),
+                    ),
+                };
+                let cells: String = row
+                    .cells
+                    .iter()
+                    .map(|cell| format!("<td>{}</td>", html_escape(cell)))
+                    .collect();
+                writeln!(out, "<tr{}>{}</tr>", style, cells)?;
+                count += 1;
+            }
+            writeln!(out, "</table>\n<p>{} records</p>\n</body>\n</html>", count)?;
+        }
+    }
+    Ok(count)
+}
+
+fn csv_line(cells: &[String]) -> String {
+    cells
+        .iter()
+        .map(|cell| {
+            if cell.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn css_color(color: Color) -> String {
+    let [r, g, b, _] = color.into_rgba8();
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn export(format: ExportFormat) -> String {
+        let rows = vec![
+            ExportRow {
+                cells: vec![String::from("1"), String::from("plain")],
+                colors: (None, None),
+            },
+            ExportRow {
+                cells: vec![String::from("22"), String::from("say \"a, <b>\"")],
+                colors: (Some(iced::color!(255, 0, 0)), None),
+            },
+        ];
+        let mut out = vec![];
+        let count = write_rows(
+            &mut out,
+            format,
+            "Report",
+            &[Field::Id, Field::LogMessage],
+            rows.into_iter(),
+        )
+        .unwrap();
+        assert_eq!(count, 2);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_export_formats() {
+        let id = Field::Id.name();
+
+        let csv = export(ExportFormat::Csv);
+        assert_eq!(csv.lines().nth(2), Some("22,\"say \"\"a, <b>\"\"\""));
+
+        let jsonl = export(ExportFormat::JsonLines);
+        let last: serde_json::Value = serde_json::from_str(jsonl.lines().last().unwrap()).unwrap();
+        assert_eq!(last[id], "22");
+
+        let txt = export(ExportFormat::Text);
+        let lines: Vec<&str> = txt.lines().collect();
+        let width = id.len().max(2);
+        assert_eq!(lines[1], format!("{:width$}  plain", "1"));
+
+        let html = export(ExportFormat::Html);
+        assert!(html.contains("<tr style=\"background: #ff0000;\">"));
+        assert!(html.contains("say &quot;a, &lt;b&gt;&quot;"));
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use iced::{
+    Element,
+    Length::Fill,
+    Task, Theme,
+    widget::{button, column, container, pick_list, row, text, text_input},
+};
+use log_reader_core::{Field, Record};
+
+use crate::LogReader;
+use crate::export::{self, ExportFormat, ExportRow, ExportScope};
+use crate::log_message::{ExportMessage, LogReaderMessage};
+
+#[derive(Debug, Default)]
+pub struct ExportDialog {
+    format: ExportFormat,
+    scope: ExportScope,
+    path: String,
+    status: String,
+}
+
+fn default_path(log_file_name: &str, format: ExportFormat) -> String {
+    let path = Path::new(log_file_name);
+    let stem = path.file_stem().map_or(String::from("log"), |stem| {
+        stem.to_string_lossy().into_owned()
+    });
+    path.with_file_name(format!("{}-export.{}", stem, format.extension()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+pub fn open(log_reader: &mut LogReader, scope: ExportScope) {
+    let format = ExportFormat::default();
+    log_reader.export_dialog = Some(ExportDialog {
+        format,
+        scope,
+        path: default_path(&log_reader.current_log_file_name, format),
+        status: String::default(),
+    });
+}
+
+/// The shown records of `scope`, in the order of the table.
+fn records(log_reader: &LogReader, scope: ExportScope) -> Vec<&Record> {
+    let events = &log_reader.events;
+    let shown = log_reader.events_filtered.iter().map(|&idx| &events[idx]);
+    match scope {
+        ExportScope::Shown => shown.collect(),
+        ExportScope::Marked => shown
+            .filter(|record| log_reader.selected_rows.contains(record.id))
+            .collect(),
+        ExportScope::Selected => log_reader._selected_records(),
+    }
+}
+
+fn export(log_reader: &LogReader, dialog: &ExportDialog) -> std::io::Result<usize> {
+    let fields: Vec<Field> = log_reader
+        .columns
+        .visible()
+        .map(|column| column.field)
+        .collect();
+    let rows = records(log_reader, dialog.scope).into_iter().map(|record| {
+        let cells = fields
+            .iter()
+            .map(|&field| log_reader.dictionary.display(field, record))
+            .collect();
+        let colors = log_reader
+            .severity
+            .level_for(record)
+            .map_or((None, None), |level| level.colors(false));
+        ExportRow { cells, colors }
+    });
+
+    let mut out = BufWriter::new(File::create(&dialog.path)?);
+    let count = export::write_rows(
+        &mut out,
+        dialog.format,
+        &log_reader.current_log_file_name,
+        &fields,
+        rows,
+    )?;
+    out.flush()?;
+    Ok(count)
+}
+
+pub fn view(log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
+    let dialog = log_reader.export_dialog.as_ref().unwrap();
+    let msg = LogReaderMessage::Export;
+
+    container(
+        container(
+            column![
+                text("Export"),
+                row![
+                    text("Rows").width(80),
+                    pick_list(ExportScope::ALL, Some(dialog.scope), move |scope| msg(
+                        ExportMessage::Scope(scope)
+                    )),
+                    text("Format"),
+                    pick_list(ExportFormat::ALL, Some(dialog.format), move |format| msg(
+                        ExportMessage::Format(format)
+                    )),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+                row![
+                    text("File").width(80),
+                    text_input("File path", &dialog.path)
+                        .on_input(move |txt| msg(ExportMessage::PathInput(txt))),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+                text("The visible columns are exported in their current order.").size(12),
+                row![
+                    text(&dialog.status).width(Fill),
+                    button("Export").on_press_maybe(
+                        (!dialog.path.is_empty()).then(|| msg(ExportMessage::Export))
+                    ),
+                    button("Close").on_press(msg(ExportMessage::CloseDialog)),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+            ]
+            .padding(16)
+            .spacing(16),
+        )
+        .width(700)
+        .style(|theme: &Theme| {
+            container::Style::default()
+                .background(theme.palette().background)
+                .border(
+                    iced::Border::default()
+                        .width(2)
+                        .color(theme.palette().success),
+                )
+        }),
+    )
+    .center(Fill)
+    .into()
+}
+
+pub fn update(log_reader: &mut LogReader, msg: LogReaderMessage) -> Task<LogReaderMessage> {
+    let msg = match msg {
+        LogReaderMessage::Export(msg) => msg,
+        LogReaderMessage::Event(iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+            key: iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape),
+            ..
+        })) => ExportMessage::CloseDialog,
+        _ => return Task::none(),
+    };
+
+    if let ExportMessage::OpenDialog(scope) = msg {
+        open(log_reader, scope);
+        return Task::none();
+    }
+
+    let log_file_name = log_reader.current_log_file_name.clone();
+    let Some(dialog) = log_reader.export_dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        ExportMessage::OpenDialog(_) => (),
+        ExportMessage::CloseDialog => log_reader.export_dialog = None,
+        ExportMessage::Format(format) => {
+            // Keep a path typed by the user, only follow the format with the default one
+            if dialog.path == default_path(&log_file_name, dialog.format) {
+                dialog.path = default_path(&log_file_name, format);
+            }
+            dialog.format = format;
+        }
+        ExportMessage::Scope(scope) => dialog.scope = scope,
+        ExportMessage::PathInput(txt) => dialog.path = txt,
+        ExportMessage::Export => {
+            let dialog = log_reader.export_dialog.as_ref().unwrap();
+            let status = match export(log_reader, dialog) {
+                Ok(count) => format!("Exported {} records to {}", count, dialog.path),
+                Err(e) => format!("Export failed. {}", e),
+            };
+            if let Some(dialog) = log_reader.export_dialog.as_mut() {
+                dialog.status = status;
+            }
+        }
+    }
+    Task::none()
+}
@@ -0,0 +1,212 @@
+use std::ops::Range;
+
+use iced::{Color, Font, advanced::text::highlighter, color, font};
+use log_reader_core::Field;
+
+use crate::search::{SearchOptions, SearchQuery, SearchScope};
+
+pub const SEARCH_MATCH_BG_COLOR: Color = color!(255, 214, 0);
+pub const FILTER_MATCH_BG_COLOR: Color = color!(110, 190, 255);
+pub const GROUP_MATCH_BG_COLOR: Color = color!(255, 140, 60);
+pub const MATCH_TXT_COLOR: Color = Color::BLACK;
+
+// The text editor can only change the text color, so the detail view uses darker tones
+const SEARCH_MATCH_TXT_COLOR_DETAIL: Color = color!(230, 120, 0);
+const FILTER_MATCH_TXT_COLOR_DETAIL: Color = color!(30, 130, 230);
+const GROUP_MATCH_TXT_COLOR_DETAIL: Color = color!(200, 50, 0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchKind {
+    Search,
+    // A capture group of a regex search match
+    Group,
+    Filter,
+}
+
+/// Splits a search match into its capture groups and the parts around them. Groups nested
+/// in an earlier group are part of that one.
+fn split_groups(
+    whole: Range<usize>,
+    groups: Vec<Range<usize>>,
+) -> impl Iterator<Item = (Range<usize>, MatchKind)> {
+    let mut parts = vec![];
+    let mut pos = whole.start;
+    for group in groups {
+        if group.start < pos {
+            continue;
+        }
+        if pos < group.start {
+            parts.push((pos..group.start, MatchKind::Search));
+        }
+        pos = group.end;
+        parts.push((group, MatchKind::Group));
+    }
+    if pos < whole.end {
+        parts.push((pos..whole.end, MatchKind::Search));
+    }
+    parts.into_iter()
+}
+
+/// Finds the parts of a cell that match the search or one of the column filters.
+#[derive(Debug, Clone, Default)]
+pub struct Matcher {
+    search: Option<SearchQuery>,
+    filter_terms: Vec<(Field, String)>,
+}
+
+impl Matcher {
+    pub fn new(search: Option<SearchQuery>, filter_terms: Vec<(Field, String)>) -> Self {
+        Self {
+            search,
+            filter_terms,
+        }
+    }
+
+    /// Sorted, non-overlapping byte ranges of the matches in `text`, search matches win
+    /// over filter matches.
+    pub fn ranges(&self, field: Field, text: &str) -> Vec<(Range<usize>, MatchKind)> {
+        let mut ranges: Vec<(Range<usize>, MatchKind)> = vec![];
+
+        if let Some(search) = &self.search
+            && (search.scope() == SearchScope::All || search.scope() == SearchScope::Field(field))
+        {
+            ranges.extend(
+                search
+                    .find_groups(text)
+                    .into_iter()
+                    .flat_map(|(whole, groups)| split_groups(whole, groups)),
+            );
+        }
+
+        for (_, term) in self.filter_terms.iter().filter(|(f, _)| *f == field) {
+            for (start, _) in text.match_indices(term.as_str()) {
+                let range = start..start + term.len();
+                if !ranges
+                    .iter()
+                    .any(|(r, _)| r.start < range.end && range.start < r.end)
+                {
+                    ranges.push((range, MatchKind::Filter));
+                }
+            }
+        }
+
+        ranges.sort_by_key(|(range, _)| range.start);
+        ranges
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HighlightSettings {
+    pub search_text: String,
+    pub search_options: SearchOptions,
+    pub filter_terms: Vec<(Field, String)>,
+}
+
+impl HighlightSettings {
+    pub fn matcher(&self) -> Matcher {
+        let search = (!self.search_text.is_empty())
+            .then(|| SearchQuery::new(&self.search_text, &self.search_options).ok())
+            .flatten();
+        Matcher::new(search, self.filter_terms.clone())
+    }
+}
+
+/// Highlights the matches of the log message in the detail view's text editor.
+pub struct MessageHighlighter {
+    settings: HighlightSettings,
+    matcher: Matcher,
+    current_line: usize,
+}
+
+impl highlighter::Highlighter for MessageHighlighter {
+    type Settings = HighlightSettings;
+    type Highlight = MatchKind;
+    type Iterator<'a> = std::vec::IntoIter<(Range<usize>, MatchKind)>;
+
+    fn new(settings: &Self::Settings) -> Self {
+        Self {
+            settings: settings.clone(),
+            matcher: settings.matcher(),
+            current_line: 0,
+        }
+    }
+
+    fn update(&mut self, new_settings: &Self::Settings) {
+        if *new_settings != self.settings {
+            *self = Self::new(new_settings);
+        }
+    }
+
+    fn change_line(&mut self, line: usize) {
+        self.current_line = self.current_line.min(line);
+    }
+
+    fn highlight_line(&mut self, line: &str) -> Self::Iterator<'_> {
+        self.current_line += 1;
+        self.matcher.ranges(Field::LogMessage, line).into_iter()
+    }
+
+    fn current_line(&self) -> usize {
+        self.current_line
+    }
+}
+
+pub fn detail_format(kind: &MatchKind, _theme: &iced::Theme) -> highlighter::Format<Font> {
+    highlighter::Format {
+        color: Some(match kind {
+            MatchKind::Search => SEARCH_MATCH_TXT_COLOR_DETAIL,
+            MatchKind::Group => GROUP_MATCH_TXT_COLOR_DETAIL,
+            MatchKind::Filter => FILTER_MATCH_TXT_COLOR_DETAIL,
+        }),
+        font: Some(Font {
+            weight: font::Weight::Bold,
+            ..Font::DEFAULT
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_wins_over_filter() {
+        let options = SearchOptions::default();
+        let matcher = Matcher::new(
+            SearchQuery::new("timeout", &options).ok(),
+            vec![
+                (Field::LogMessage, String::from("out")),
+                (Field::LogMessage, String::from("retry")),
+            ],
+        );
+
+        assert_eq!(
+            matcher.ranges(Field::LogMessage, "retry after timeout"),
+            vec![(0..5, MatchKind::Filter), (12..19, MatchKind::Search)]
+        );
+        assert!(matcher.ranges(Field::ModuleName, "timeout").is_empty());
+    }
+
+    #[test]
+    fn test_capture_groups() {
+        let options = SearchOptions {
+            regex: true,
+            ..SearchOptions::default()
+        };
+        let matcher = Matcher::new(
+            SearchQuery::new(r"disk (\w+) at (\d+)(%)?", &options).ok(),
+            vec![],
+        );
+
+        assert_eq!(
+            matcher.ranges(Field::LogMessage, "disk sda1 at 95% full"),
+            vec![
+                (0..5, MatchKind::Search),
+                (5..9, MatchKind::Group),
+                (9..13, MatchKind::Search),
+                (13..15, MatchKind::Group),
+                (15..16, MatchKind::Group),
+            ]
+        );
+    }
+}
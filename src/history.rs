@@ -0,0 +1,135 @@
+use log_reader_core::FilterValues;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryState {
+    pub filters: FilterValues,
+    pub highlighted_scroll_offset: usize,
+    pub scroll_value: f64,
+}
+
+/// Back/forward stacks of filter states and cursor positions.
+#[derive(Debug, Default)]
+pub struct History {
+    back: Vec<HistoryState>,
+    forward: Vec<HistoryState>,
+    // Filter being edited when the last state was pushed, so typing a word records a single entry
+    last_edit: Option<&'static str>,
+}
+
+const HISTORY_LIMIT: usize = 500;
+
+impl History {
+    /// Records the state before a jump or a filter change, drops the forward history.
+    pub fn push(&mut self, state: HistoryState) {
+        self.last_edit = None;
+        self.forward.clear();
+        if self.back.last() == Some(&state) {
+            return;
+        }
+        if self.back.len() >= HISTORY_LIMIT {
+            self.back.remove(0);
+        }
+        self.back.push(state);
+    }
+
+    /// Records the state before `filter` is edited, repeated edits of the same filter are merged.
+    pub fn push_edit(&mut self, state: HistoryState, filter: &'static str) {
+        if self.last_edit == Some(filter) {
+            return;
+        }
+        self.push(state);
+        self.last_edit = Some(filter);
+    }
+
+    pub fn back(&mut self, current: HistoryState) -> Option<HistoryState> {
+        self.last_edit = None;
+        let state = self.back.pop()?;
+        self.forward.push(current);
+        Some(state)
+    }
+
+    pub fn forward(&mut self, current: HistoryState) -> Option<HistoryState> {
+        self.last_edit = None;
+        let state = self.forward.pop()?;
+        self.back.push(current);
+        Some(state)
+    }
+
+    /// Goes back to the last state with different filters, skipping plain jumps.
+    pub fn undo(&mut self, current: HistoryState) -> Option<HistoryState> {
+        let idx = self
+            .back
+            .iter()
+            .rposition(|state| state.filters != current.filters)?;
+        let mut current = current;
+        while self.back.len() > idx + 1 {
+            current = self.back(current)?;
+        }
+        self.back(current)
+    }
+
+    /// Goes forward to the latest state of the next filter change.
+    pub fn redo(&mut self, current: HistoryState) -> Option<HistoryState> {
+        let next = self
+            .forward
+            .iter()
+            .rposition(|state| state.filters != current.filters)?;
+        let filters = self.forward[next].filters.clone();
+        let idx = self.forward[..next]
+            .iter()
+            .rposition(|state| state.filters != filters)
+            .map_or(0, |idx| idx + 1);
+        let mut current = current;
+        while self.forward.len() > idx + 1 {
+            current = self.forward(current)?;
+        }
+        self.forward(current)
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(module_name: &str, offset: usize) -> HistoryState {
+        HistoryState {
+            filters: FilterValues {
+                module_name: module_name.to_owned(),
+                ..FilterValues::default()
+            },
+            highlighted_scroll_offset: offset,
+            scroll_value: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_edits_are_merged() {
+        let mut history = History::default();
+        history.push_edit(state("", 0), "module_name");
+        history.push_edit(state("N", 0), "module_name");
+        history.push_edit(state("Ne", 0), "module_name");
+
+        assert_eq!(history.back(state("Net", 0)), Some(state("", 0)));
+        assert!(!history.can_go_back());
+        assert_eq!(history.forward(state("", 0)), Some(state("Net", 0)));
+    }
+
+    #[test]
+    fn test_undo_skips_jumps() {
+        let mut history = History::default();
+        history.push_edit(state("", 0), "module_name");
+        history.push(state("Net", 10));
+        history.push(state("Net", 20));
+
+        assert_eq!(history.undo(state("Net", 30)), Some(state("", 0)));
+        assert_eq!(history.redo(state("", 0)), Some(state("Net", 30)));
+    }
+}
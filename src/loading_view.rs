@@ -1,15 +1,14 @@
-use iced::{Element, widget::{text, container, progress_bar}};
+use iced::{
+    Element,
+    widget::{container, text},
+};
 
 use crate::{LogReader, log_message::LogReaderMessage};
 
-
-
-pub fn view(log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
+pub fn view(_log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
     // progress_bar(0..=100, value)
 
     container(text("Hello")).into()
 }
 
-pub fn update(log_reader: &mut LogReader, msg: LogReaderMessage) {
-
-}
\ No newline at end of file
+pub fn update(_log_reader: &mut LogReader, _msg: LogReaderMessage) {}
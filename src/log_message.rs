@@ -1,19 +1,206 @@
+use std::sync::Arc;
+
 use iced::{Event as IcedEvent, widget::text_editor, window};
+use log_reader_core::{Field, FilterMode, SearchIndex};
+
+use crate::bookmarks::MarkColor;
+use crate::context_menu::MenuTarget;
+use crate::copy::CopyFormat;
+use crate::export::{ExportFormat, ExportScope};
+use crate::report::ReportFormat;
+use crate::rules::RuleOp;
+use crate::search::SearchScope;
+
+#[derive(Debug, Clone)]
+pub enum PresetMessage {
+    Selected(String),
+    OpenDialog,
+    CloseDialog,
+    NameInput(String),
+    PathInput(String),
+    Save,
+    Rename(String),
+    Delete(String),
+    SetDefault(String),
+    ClearDefault,
+    Import,
+    Export,
+}
+
+#[derive(Debug, Clone)]
+pub enum ResultsMessage {
+    Toggle,
+    SwitchDock,
+    Pin,
+    Unpin(usize),
+    Jump(usize),
+}
+
+#[derive(Debug, Clone)]
+pub enum TimelineMessage {
+    Toggle,
+    /// Index of the clicked bar
+    Clicked(usize),
+    /// First and last bar dragged across
+    Selected(usize, usize),
+    /// Zoom factor and where the cursor was, from 0 (left) to 1 (right)
+    Zoom(f64, f64),
+    ResetZoom,
+    RemoveDateBefore,
+}
+
+#[derive(Debug, Clone)]
+pub enum RuleMessage {
+    OpenDialog,
+    CloseDialog,
+    Add,
+    Delete(usize),
+    MoveUp(usize),
+    MoveDown(usize),
+    Enabled(usize, bool),
+    Field(usize, Field),
+    Op(usize, RuleOp),
+    Pattern(usize, String),
+    Background(usize, String),
+    TextColor(usize, String),
+}
+
+#[derive(Debug, Clone)]
+pub enum SeverityMessage {
+    OpenDialog,
+    CloseDialog,
+    Field(Field),
+    Add,
+    Delete(usize),
+    Name(usize, String),
+    Value(usize, String),
+    LightBackground(usize, String),
+    LightText(usize, String),
+    DarkBackground(usize, String),
+    DarkText(usize, String),
+}
 
 #[derive(Debug, Clone)]
-pub enum FilterMessage {}
+pub enum DictionaryMessage {
+    OpenDialog,
+    CloseDialog,
+    PathInput(String),
+    Load,
+    Unload,
+}
+
+#[derive(Debug, Clone)]
+pub enum ColumnMessage {
+    HeaderPressed(Field),
+    HeaderEntered(Field),
+    ResizePressed(Field),
+    CloseMenu,
+    Visible(Field, bool),
+    Reset,
+}
+
+#[derive(Debug, Clone)]
+pub enum SelectionMessage {
+    RowClicked(usize),
+    MarkAll,
+    Copy,
+    Export,
+    FilterTo,
+    Clear,
+}
+
+#[derive(Debug, Clone)]
+pub enum BookmarkMessage {
+    TogglePanel,
+    ShowColor(Option<MarkColor>),
+    Jump(u64),
+    Note(u64, String),
+    Color(u64, MarkColor),
+    Delete(u64),
+}
+
+#[derive(Debug, Clone)]
+pub enum ExportMessage {
+    OpenDialog(ExportScope),
+    CloseDialog,
+    Format(ExportFormat),
+    Scope(ExportScope),
+    PathInput(String),
+    Export,
+}
+
+#[derive(Debug, Clone)]
+pub enum ReportMessage {
+    OpenDialog,
+    CloseDialog,
+    Format(ReportFormat),
+    ContextInput(String),
+    PathInput(String),
+    Save,
+    Copy,
+}
+
+#[derive(Debug, Clone)]
+pub enum ContextMenuMessage {
+    Open(MenuTarget),
+    Close,
+    Mark,
+    Details,
+    Copy,
+    FilterByValue,
+    ExcludeValue,
+    ShowSurrounding,
+    Sort(bool),
+    ClearSort,
+    SetFilterMode(FilterMode),
+    ClearFilter,
+    ClearExclusions,
+    ClearAllFilters,
+    Hide,
+    Columns,
+}
 
 #[derive(Debug, Clone)]
 pub enum LogReaderMessage {
     // Change(usize),
     ScrollChanged(f64),
     Event(IcedEvent),
-    WindowClosed(window::Id),
+    WindowCloseRequested(window::Id),
+    CancelClose,
+
+    SearchIndexBuilt(Result<Arc<SearchIndex>, String>),
 
     TableEntered,
     TableLeft,
+    CellHovered(Field),
+    RowDoubleClicked(usize),
+    RemoveExclusion(usize),
+    RemoveKept(usize),
+    RemoveInclusion,
+    DismissStatus,
+    CopyFormatSelected(CopyFormat),
 
     SearchInputChanged(String),
+    SearchRegexToggled(bool),
+    SearchCaseInsensitiveToggled(bool),
+    SearchWholeWordToggled(bool),
+    SearchScopeSelected(SearchScope),
+
+    Preset(PresetMessage),
+    Results(ResultsMessage),
+    Timeline(TimelineMessage),
+    Rule(RuleMessage),
+    Severity(SeverityMessage),
+    Dictionary(DictionaryMessage),
+    Column(ColumnMessage),
+    Selection(SelectionMessage),
+    Bookmark(BookmarkMessage),
+    Export(ExportMessage),
+    Report(ReportMessage),
+    ContextMenu(ContextMenuMessage),
+
+    HistoryBack,
+    HistoryForward,
 
     TextEditorEdit(text_editor::Action),
 
@@ -1,23 +1,95 @@
 #![cfg_attr(not(test), windows_subsystem = "windows")]
-use iced::widget::{column, container, rich_text, row, span, stack, text, text_editor};
+use iced::never;
+use iced::widget::{button, column, container, rich_text, row, span, stack, text, text_editor};
 use iced::{Element, Event as IcedEvent, Fill, Subscription, Task, Theme, color, event, window};
-use iced::{Length, never};
+use log_reader_core::{Dictionary, Field, FilterValues, Record, SearchIndex};
 
+use std::collections::HashSet;
 use std::env;
+use std::io;
+use std::sync::Arc;
 
+use crate::bookmarks::{Bookmarks, MarkColor};
+use crate::columns::{ColumnDrag, ColumnLayout, MIN_COLUMN_WIDTH};
+use crate::context_menu::ContextMenu;
+use crate::copy::CopyFormat;
+use crate::dictionary_view::DictionaryDialog;
+use crate::export_view::ExportDialog;
+use crate::highlight::{HighlightSettings, SEARCH_MATCH_BG_COLOR};
+use crate::history::{History, HistoryState};
 use crate::log_message::LogReaderMessage;
-use crate::record::Record;
+use crate::presets::Presets;
+use crate::presets_view::PresetsDialog;
+use crate::report_view::ReportDialog;
+use crate::results_view::{Dock, ResultList};
+use crate::rules::HighlightRules;
+use crate::scrollbar::Marker;
+use crate::search::{SearchOptions, SearchQuery};
+use crate::selection::Selection;
+use crate::session::Session;
+use crate::severity::SeverityScheme;
+use crate::sort::SortKey;
+use crate::timeline::Histogram;
 
+mod bookmarks;
+mod bookmarks_view;
+mod cli;
+mod columns;
+mod config;
+mod context_menu;
+mod copy;
 mod detail_view;
+mod dictionary;
+mod dictionary_view;
+mod export;
+mod export_view;
+mod highlight;
+mod history;
+#[allow(dead_code)]
 mod loading_view;
 mod log_message;
-mod record;
+mod presets;
+mod presets_view;
+mod report;
+mod report_view;
+mod results_view;
+mod rules;
+mod rules_view;
+mod scrollbar;
+mod search;
+mod selection;
+mod session;
+mod severity;
+mod severity_view;
+mod sort;
 mod table_view;
+mod timeline;
+mod timeline_view;
+mod virtual_list;
+
+const SCROLLBAR_WIDTH: f32 = 12.0;
+const MINIMAP_BUCKETS: usize = 1000;
+// Rough size of the default font, used to guess how many lines an expanded message takes
+const CHAR_WIDTH: f32 = 8.0;
+const LINE_HEIGHT: f32 = 21.0;
+
+type TimeRange = (
+    chrono::DateTime<chrono::Local>,
+    chrono::DateTime<chrono::Local>,
+);
 
 fn main() -> iced::Result {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--no-gui") {
+        #[cfg(windows)]
+        cli::attach_console();
+        std::process::exit(cli::run(&args, &mut io::stdout().lock()));
+    }
+
     iced::application(LogReader::new, LogReader::update, LogReader::view)
         .subscription(LogReader::subscribtion)
         .window_size((1500, 800))
+        .exit_on_close_request(false)
         .title(LogReader::title)
         .run()
 }
@@ -35,205 +107,350 @@ fn main() -> iced::Result {
 struct ViewDetail {
     record: Record,
     content: text_editor::Content,
-}
-
-pub struct LogColumn<'a, 'b, T> {
-    pub header: Element<'a, LogReaderMessage>,
-    pub view: Box<dyn Fn(T) -> Element<'a, LogReaderMessage> + 'b>,
-    pub width: Length,
-}
-
-impl<'a, 'b, T> LogColumn<'a, 'b, T> {
-    pub fn new<E>(
-        header: impl Into<Element<'a, LogReaderMessage>>,
-        view: impl Fn(T) -> E + 'b,
-        width: impl Into<Length>,
-    ) -> LogColumn<'a, 'b, T>
-    where
-        E: Into<Element<'a, LogReaderMessage>>,
-    {
-        LogColumn {
-            header: header.into(),
-            view: Box::new(move |r| view(r).into()),
-            width: width.into(),
-        }
-    }
-}
-
-#[derive(Debug)]
-pub struct MyFilter {
-    value: String,
-    message: fn(String) -> LogReaderMessage,
-}
-
-#[derive(Debug)]
-pub struct MyFilters {
-    log_name: MyFilter,
-    date: MyFilter,
-    computer_name: MyFilter,
-    process_id: MyFilter,
-    process_user: MyFilter,
-    module_name: MyFilter,
-    message_id: MyFilter,
-    log_level: MyFilter,
-    facility: MyFilter,
-    log_message: MyFilter,
-}
-
-impl Default for MyFilters {
-    fn default() -> Self {
-        Self {
-            log_name: MyFilter {
-                value: String::default(),
-                message: LogReaderMessage::IDInput,
-            },
-            date: MyFilter {
-                value: String::default(),
-                message: LogReaderMessage::DateInput,
-            },
-            computer_name: MyFilter {
-                value: String::default(),
-                message: LogReaderMessage::ComputerNameInput,
-            },
-            process_id: MyFilter {
-                value: String::default(),
-                message: LogReaderMessage::ProcessIDInput,
-            },
-            process_user: MyFilter {
-                value: String::default(),
-                message: LogReaderMessage::ProcessUserInput,
-            },
-            module_name: MyFilter {
-                value: String::default(),
-                message: LogReaderMessage::ModuleNameInput,
-            },
-            message_id: MyFilter {
-                value: String::default(),
-                message: LogReaderMessage::MessageIDInput,
-            },
-            log_level: MyFilter {
-                value: String::default(),
-                message: LogReaderMessage::LogLevelInput,
-            },
-            facility: MyFilter {
-                value: String::default(),
-                message: LogReaderMessage::FacilityInput,
-            },
-            log_message: MyFilter {
-                value: String::default(),
-                message: LogReaderMessage::LogMessageInput,
-            },
-        }
-    }
+    highlight: HighlightSettings,
+    // Line and byte range of every search or filter match in the message
+    matches: Vec<(usize, std::ops::Range<usize>)>,
+    current_match: Option<usize>,
 }
 
 #[derive(Debug)]
 pub struct LogReader {
     current_log_file_name: String,
+    session_key: String,
 
-    events: Vec<Record>,
+    events: Arc<Vec<Record>>,
     events_filtered: Vec<usize>,
+    // Built in the background after loading, searches fall back to scanning until then
+    // or when building it failed
+    search_index: Option<Arc<SearchIndex>>,
 
     // Is dynamically calculated from cursor, so it is possible to get row_offset number
     // that is greater than rows in the table
     highlighted_scroll_offset: usize,
     scroll_value: f64,
+    toolbar_height: f32,
     header_height: f32,
     row_height: f32,
     row_padding: f32,
     rows_visible: u32,
     window_height: f32,
+    window_width: f32,
 
-    selected_rows: Vec<u64>,
+    selected_rows: Bookmarks,
+    bookmarks_panel: bool,
+    // Color given to new marks and the only one n / N visit, all colors when None
+    mark_color: Option<MarkColor>,
+    selection: Selection,
+    copy_format: CopyFormat,
+    highlighted_column: Field,
+    modifiers: iced::keyboard::Modifiers,
     _mouse_on_table: bool,
+    _mouse_x: f32,
     _mouse_y: f32,
 
     view_detail: Option<ViewDetail>,
 
-    filters: MyFilters,
+    filters: FilterValues,
+    presets: Presets,
+    presets_dialog: Option<PresetsDialog>,
+    history: History,
+    rules: HighlightRules,
+    rules_dialog: bool,
+    severity: SeverityScheme,
+    severity_dialog: bool,
+    /// Bumped on every edit of the severity levels
+    severity_generation: u64,
+    dictionary: Dictionary,
+    dictionary_dialog: Option<DictionaryDialog>,
+    export_dialog: Option<ExportDialog>,
+    report_dialog: Option<ReportDialog>,
+    sort_keys: Vec<SortKey>,
+    /// Bumped whenever events_filtered is rebuilt or reordered
+    rows_generation: u64,
+    // Position in events_filtered of each record, built for sorted rows when the search
+    // index needs it, with the rows_generation it was built for
+    row_positions: Option<(u64, Vec<usize>)>,
+    minimap: Vec<Marker>,
+    minimap_key: Option<(u64, u64, u64, u64)>,
+    timeline: bool,
+    // Time range shown by the timeline, the span of the shown records when None
+    timeline_zoom: Option<TimeRange>,
+    histogram: Option<Histogram>,
+    histogram_key: Option<(u64, Option<TimeRange>, usize, u64)>,
+    expanded_rows: HashSet<u64>,
+    log_format: String,
+    columns: ColumnLayout,
+    column_drag: Option<ColumnDrag>,
+    column_menu: bool,
+    context_menu: Option<ContextMenu>,
     //current_screen: ScreenView,
     searching_bar_id: iced::widget::Id,
     searching_bar: bool,
     searching_text: String,
+    search_options: SearchOptions,
+    search_query: Option<SearchQuery>,
+    search_matches: Option<Vec<usize>>,
+    /// Bumped whenever search_matches is computed or dropped
+    search_generation: u64,
+    search_status: String,
+    search_error: Option<String>,
+    search_results: Option<ResultList>,
+    pinned_results: Vec<ResultList>,
+    results_panel: bool,
+    results_dock: Dock,
+
+    // Failures outside of any dialog, shown in the toolbar
+    status_error: Option<String>,
+    // The session could not be saved when closing, shown over every view until the window
+    // is closed anyway or the close is cancelled
+    close_error: Option<(window::Id, String)>,
 
     show_help: bool,
     //loading: u8
 }
 
 impl LogReader {
-    fn new() -> Self {
-        let mut args = env::args();
-        args.next();
-        let file_name = match args.next() {
+    fn new() -> (Self, Task<LogReaderMessage>) {
+        // --clean opens the log without restoring the last session
+        let (flags, args): (Vec<String>, Vec<String>) =
+            env::args().skip(1).partition(|arg| arg.starts_with("--"));
+        let clean = flags.iter().any(|flag| flag == "--clean");
+        let file_name = match args.into_iter().next() {
             Some(arg) => arg,
             None => panic!("No input found."),
         };
 
-        let events = match Record::read_records() {
+        let events = match Record::read_records(&file_name) {
             Ok(events) => events,
             Err(e) => panic!("Error while parsing input file. {}", e),
         };
-        let events = events;
+        let events = Arc::new(events);
+
+        let index_events = events.clone();
+        let build_index = Task::perform(
+            async move {
+                tokio::task::spawn_blocking(move || SearchIndex::build(&index_events))
+                    .await
+                    .map(Arc::new)
+                    .map_err(|e| e.to_string())
+            },
+            LogReaderMessage::SearchIndexBuilt,
+        );
 
-        Self {
+        let log_format = columns::log_format(&file_name);
+        let session_key = session::session_key(&file_name);
+        let mut log_reader = Self {
             current_log_file_name: file_name,
+            session_key,
             events_filtered: (0..events.len()).collect(),
             events,
+            search_index: None,
             highlighted_scroll_offset: 0,
             scroll_value: 0.0,
+            toolbar_height: 40.0,
             header_height: 75.0,
             row_height: 35.0,
             row_padding: 5.0,
             rows_visible: 0,
             window_height: 0.0,
-            selected_rows: vec![],
+            window_width: 0.0,
+            selected_rows: Bookmarks::default(),
+            bookmarks_panel: false,
+            mark_color: None,
+            selection: Selection::default(),
+            copy_format: CopyFormat::default(),
+            highlighted_column: Field::LogMessage,
+            modifiers: iced::keyboard::Modifiers::default(),
             _mouse_on_table: false,
             view_detail: None,
             // current_screen: ScreenView::Loading,
+            _mouse_x: 0.0,
             _mouse_y: 0.0,
-            filters: MyFilters::default(),
+            filters: FilterValues::default(),
+            presets: Presets::load(),
+            presets_dialog: None,
+            history: History::default(),
+            rules: HighlightRules::load(),
+            rules_dialog: false,
+            severity: SeverityScheme::load(),
+            severity_dialog: false,
+            severity_generation: 0,
+            dictionary: dictionary::load_last(),
+            dictionary_dialog: None,
+            export_dialog: None,
+            report_dialog: None,
+            sort_keys: vec![],
+            rows_generation: 0,
+            row_positions: None,
+            minimap: vec![],
+            minimap_key: None,
+            timeline: false,
+            timeline_zoom: None,
+            histogram: None,
+            histogram_key: None,
+            expanded_rows: HashSet::new(),
+            columns: ColumnLayout::load(&log_format),
+            log_format,
+            column_drag: None,
+            column_menu: false,
+            context_menu: None,
             searching_bar_id: iced::widget::Id::unique(),
             searching_bar: false,
             searching_text: String::default(),
+            search_options: SearchOptions::default(),
+            search_query: None,
+            search_matches: None,
+            search_generation: 0,
+            search_status: String::default(),
+            search_error: None,
+            search_results: None,
+            pinned_results: vec![],
+            results_panel: false,
+            results_dock: Dock::default(),
+            status_error: None,
+            close_error: None,
             show_help: false,
+        };
+        // The saved layout may hide the message column
+        log_reader.highlighted_column = log_reader.columns.shown(Field::LogMessage);
+
+        match session::load(&log_reader.session_key)
+            .filter(|session| !clean && session.fits(&log_reader.events))
+        {
+            Some(session) => log_reader._restore_session(session),
+            None => {
+                if let Some(preset) = log_reader.presets.default_preset().cloned() {
+                    presets_view::apply_preset(&mut log_reader, preset);
+                }
+            }
+        }
+
+        (log_reader, build_index)
+    }
+
+    fn _session(&self) -> Session {
+        Session {
+            marks: self.selected_rows.iter().cloned().collect(),
+            filters: self.filters.clone(),
+            search_text: self.searching_text.clone(),
+            search_options: self.search_options.clone(),
+            scroll_value: self.scroll_value,
+            highlighted_scroll_offset: self.highlighted_scroll_offset,
+            ..Session::new(&self.events)
         }
     }
 
+    fn _restore_session(&mut self, mut session: Session) {
+        let records = self.events.len();
+        session.marks.retain(|mark| (mark.id as usize) < records);
+        self.selected_rows = Bookmarks::from_marks(session.marks);
+        self.filters = session.filters;
+        table_view::apply_filter(self);
+
+        self.searching_text = session.search_text;
+        self.search_options = session.search_options;
+        self._reset_search();
+
+        let last = self.events_filtered.len().saturating_sub(1);
+        self.highlighted_scroll_offset = session.highlighted_scroll_offset.min(last);
+        self.scroll_value = session.scroll_value.min(last as f64);
+    }
+
     fn title(&self) -> String {
-        return format!("Log Reader - {}", self.current_log_file_name);
+        format!("Log Reader - {}", self.current_log_file_name)
     }
 
     fn update(&mut self, msg: LogReaderMessage) -> Task<LogReaderMessage> {
-        let ret: Option<Task<LogReaderMessage>>;
-
-        match &msg {
-            LogReaderMessage::Event(event) => match event {
-                IcedEvent::Keyboard(keyboard_event) => match keyboard_event {
-                    iced::keyboard::Event::KeyReleased { key, .. } => match *key {
-                        iced::keyboard::Key::Named(iced::keyboard::key::Named::F1) => {
-                            self.show_help = true;
-                            return Task::none();
-                        }
-                        iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape)
-                            if self.show_help == true =>
-                        {
-                            self.show_help = false;
-                            return Task::none();
-                        }
-                        _ => (),
-                    },
-                    _ => (),
-                },
+        if let LogReaderMessage::SearchIndexBuilt(index) = &msg {
+            match index {
+                Ok(index) => self.search_index = Some(index.clone()),
+                Err(e) => {
+                    self.status_error = Some(format!(
+                        "Failed to build the search index, searches scan every record. {}",
+                        e
+                    ))
+                }
+            }
+            return Task::none();
+        }
+
+        if let LogReaderMessage::WindowCloseRequested(id) = msg {
+            // Closing again while the failure is shown quits without the session
+            if self.close_error.is_none()
+                && let Err(e) = session::save(&self.session_key, self._session())
+            {
+                self.close_error = Some((id, format!("Failed to save the session. {}", e)));
+                return Task::none();
+            }
+            return window::close(id);
+        }
+
+        if let LogReaderMessage::CancelClose = msg {
+            self.close_error = None;
+            return Task::none();
+        }
+
+        if let LogReaderMessage::Event(IcedEvent::Keyboard(keyboard_event)) = &msg
+            && let iced::keyboard::Event::KeyReleased { key, .. } = keyboard_event
+        {
+            match *key {
+                iced::keyboard::Key::Named(iced::keyboard::key::Named::F1) => {
+                    self.show_help = true;
+                    return Task::none();
+                }
+                iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape)
+                    if self.close_error.is_some() =>
+                {
+                    self.close_error = None;
+                    return Task::none();
+                }
+                iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape)
+                    if self.show_help =>
+                {
+                    self.show_help = false;
+                    return Task::none();
+                }
                 _ => (),
-            },
-            _ => (),
+            }
         }
 
-        if self.view_detail.is_some() {
-            ret = Some(detail_view::update(self, msg));
-        } else {
-            ret = Some(table_view::update(self, msg));
+        if let LogReaderMessage::Event(IcedEvent::Keyboard(
+            iced::keyboard::Event::ModifiersChanged(modifiers),
+        )) = msg
+        {
+            self.modifiers = modifiers;
+        }
+
+        if let LogReaderMessage::Event(IcedEvent::Window(window::Event::Resized(iced::Size {
+            width,
+            height,
+        }))) = msg
+        {
+            self.window_width = width;
+            self.window_height = height;
+            self._update_rows_visible();
         }
+
+        let ret = if self.presets_dialog.is_some() || matches!(msg, LogReaderMessage::Preset(_)) {
+            Some(presets_view::update(self, msg))
+        } else if self.rules_dialog || matches!(msg, LogReaderMessage::Rule(_)) {
+            Some(rules_view::update(self, msg))
+        } else if self.severity_dialog || matches!(msg, LogReaderMessage::Severity(_)) {
+            Some(severity_view::update(self, msg))
+        } else if self.dictionary_dialog.is_some() || matches!(msg, LogReaderMessage::Dictionary(_))
+        {
+            Some(dictionary_view::update(self, msg))
+        } else if self.export_dialog.is_some() || matches!(msg, LogReaderMessage::Export(_)) {
+            Some(export_view::update(self, msg))
+        } else if self.report_dialog.is_some() || matches!(msg, LogReaderMessage::Report(_)) {
+            Some(report_view::update(self, msg))
+        } else if self.view_detail.is_some() {
+            Some(detail_view::update(self, msg))
+        } else {
+            Some(table_view::update(self, msg))
+        };
+        self._refresh_minimap();
+        self._refresh_timeline();
         ret.unwrap_or(Task::none())
 
         /*
@@ -280,12 +497,86 @@ impl LogReader {
                         .align_x(text::Alignment::Right)
                     ],
                     row![
-                        rich_text([txt_red("M\n"), txt_red("n\n"), txt_red("N\n"),]),
+                        rich_text([
+                            txt_red("m\n"),
+                            txt_red("n\n"),
+                            txt_red("N\n"),
+                            txt_red("F2\n"),
+                        ]),
                         iced::widget::space().width(Fill),
                         rich_text([
                             txt_green("Mark Row\n"),
                             txt_green("Go to Next Mark\n"),
                             txt_green("Go to Previous Mark\n"),
+                            txt_green("Show Bookmarks\n"),
+                        ])
+                        .align_x(text::Alignment::Right),
+                    ],
+                    row![
+                        rich_text([
+                            txt_red("Ctrl + P\n"),
+                            txt_red("Alt + Left / Alt + Right\n"),
+                            txt_red("Ctrl + Z / Ctrl + Y\n"),
+                            txt_red("F4\n"),
+                            txt_red("Ctrl + H\n"),
+                        ]),
+                        iced::widget::space().width(Fill),
+                        rich_text([
+                            txt_green("Manage Filter Presets\n"),
+                            txt_green("Navigate Back / Forward\n"),
+                            txt_green("Undo / Redo Filter Change\n"),
+                            txt_green("Show Search Results Panel\n"),
+                            txt_green("Edit Highlight Rules\n"),
+                        ])
+                        .align_x(text::Alignment::Right),
+                    ],
+                    row![
+                        rich_text([
+                            txt_red("Arrow Left / Arrow Right\n"),
+                            txt_red("f\n"),
+                            txt_red("x\n"),
+                            txt_red("Click Header\n"),
+                            txt_red("Shift + Click Header\n"),
+                            txt_red("Drag Header / Header Border\n"),
+                            txt_red("Right Click Row / Header\n"),
+                            txt_red("e\n"),
+                            txt_red("E\n"),
+                            txt_red("Shift + Mouse Scroll\n"),
+                        ]),
+                        iced::widget::space().width(Fill),
+                        rich_text([
+                            txt_green("Highlight Column\n"),
+                            txt_green("Filter by Cell Value\n"),
+                            txt_green("Exclude Cell Value\n"),
+                            txt_green("Sort by Column\n"),
+                            txt_green("Add Sort Column\n"),
+                            txt_green("Move / Resize Column\n"),
+                            txt_green("Open Context Menu\n"),
+                            txt_green("Expand / Collapse Row\n"),
+                            txt_green("Collapse All Rows\n"),
+                            txt_green("Scroll Log Message Sideways\n"),
+                        ])
+                        .align_x(text::Alignment::Right),
+                    ],
+                    row![
+                        rich_text([
+                            txt_red("Click\n"),
+                            txt_red("Shift + Click / Shift + Arrow\n"),
+                            txt_red("Ctrl + Click\n"),
+                            txt_red("Enter / Double Click\n"),
+                            txt_red("Escape\n"),
+                            txt_red("Ctrl + C\n"),
+                            txt_red("Ctrl + Shift + C\n"),
+                        ]),
+                        iced::widget::space().width(Fill),
+                        rich_text([
+                            txt_green("Select Row\n"),
+                            txt_green("Select Range\n"),
+                            txt_green("Add / Remove Row\n"),
+                            txt_green("Show Row Details\n"),
+                            txt_green("Clear Selection\n"),
+                            txt_green("Copy Selected or Highlighted Rows\n"),
+                            txt_green("Copy Highlighted Cell\n"),
                         ])
                         .align_x(text::Alignment::Right),
                     ],
@@ -324,45 +615,311 @@ impl LogReader {
         .into()
     }
 
+    fn _build_close_dialog(&self) -> Element<'_, LogReaderMessage> {
+        let Some((id, error)) = &self.close_error else {
+            return column![].into();
+        };
+
+        container(
+            container(
+                column![
+                    text(error),
+                    row![
+                        button("Close Anyway")
+                            .style(button::danger)
+                            .on_press(LogReaderMessage::WindowCloseRequested(*id)),
+                        iced::widget::space().width(Fill),
+                        button("Cancel").on_press(LogReaderMessage::CancelClose),
+                    ],
+                ]
+                .padding(16)
+                .spacing(16),
+            )
+            .width(500)
+            .style(|theme: &Theme| {
+                container::Style::default()
+                    .background(theme.palette().background)
+                    .border(
+                        iced::Border::default()
+                            .width(2)
+                            .color(theme.palette().danger),
+                    )
+            }),
+        )
+        .center(Fill)
+        .into()
+    }
+
     fn view(&self) -> Element<'_, LogReaderMessage> {
         let mut main_view = stack!();
         if self.view_detail.is_some() {
-            main_view = main_view.push(detail_view::view(&self));
+            main_view = main_view.push(detail_view::view(self));
         } else {
-            main_view = main_view.push(table_view::view(&self));
+            main_view = main_view.push(table_view::view(self));
+        }
+
+        if self.presets_dialog.is_some() {
+            main_view = main_view.push(presets_view::view(self));
+        }
+
+        if self.rules_dialog {
+            main_view = main_view.push(rules_view::view(self));
+        }
+
+        if self.severity_dialog {
+            main_view = main_view.push(severity_view::view(self));
+        }
+
+        if self.dictionary_dialog.is_some() {
+            main_view = main_view.push(dictionary_view::view(self));
+        }
+
+        if self.export_dialog.is_some() {
+            main_view = main_view.push(export_view::view(self));
+        }
+
+        if self.report_dialog.is_some() {
+            main_view = main_view.push(report_view::view(self));
         }
 
         if self.show_help {
             main_view = main_view.push(self._build_help_dialog());
         }
 
+        if self.close_error.is_some() {
+            main_view = main_view.push(self._build_close_dialog());
+        }
+
         main_view.into()
     }
 
     fn subscribtion(&self) -> Subscription<LogReaderMessage> {
         Subscription::batch([
             event::listen().map(LogReaderMessage::Event),
-            window::close_events().map(LogReaderMessage::WindowClosed),
+            window::close_requests().map(LogReaderMessage::WindowCloseRequested),
         ])
     }
 
+    /// Width left for the log message column, used to wrap expanded rows.
+    fn _message_column_width(&self) -> f32 {
+        let fixed: f32 = self.columns.visible().filter_map(|c| c.width).sum();
+        let panel = if self.results_panel {
+            (1 + self.pinned_results.len()).min(3) as f32 * results_view::PANEL_WIDTH
+        } else {
+            0.0
+        };
+        (self.window_width - fixed - panel - SCROLLBAR_WIDTH).max(MIN_COLUMN_WIDTH)
+    }
+
+    /// Height of the row of the event, expanded rows grow to fit their whole message.
+    fn _row_height(&self, event_idx: usize) -> f32 {
+        let record = &self.events[event_idx];
+        if !self.expanded_rows.contains(&record.id) {
+            return self.row_height;
+        }
+
+        let text_width = self._message_column_width() - 2.0 * self.row_padding;
+        let chars_per_line = ((text_width / CHAR_WIDTH) as usize).max(1);
+        let lines: usize = record
+            .log_message
+            .lines()
+            .map(|line| line.chars().count().div_ceil(chars_per_line).max(1))
+            .sum();
+
+        (lines.max(1) as f32 * LINE_HEIGHT + 2.0 * self.row_padding).max(self.row_height)
+    }
+
+    /// Number of rows shown from the row at `from`, the last one may be cut off.
+    fn _rows_fitting(&self, from: usize) -> usize {
+        let area = self.rows_visible as f32 * self.row_height;
+        let mut used = 0.0;
+        let mut count = 0;
+        for &event_idx in self.events_filtered.iter().skip(from) {
+            if used > area {
+                break;
+            }
+            used += self._row_height(event_idx);
+            count += 1;
+        }
+        count.max(1)
+    }
+
+    /// Position in events_filtered of the row at `y` pixels below the header.
+    fn _row_at(&self, y: f32) -> usize {
+        let from = self.scroll_value as usize;
+        // Part of the first row may be scrolled out of view
+        let mut bottom = -(self.scroll_value.fract() as f32)
+            * self
+                .events_filtered
+                .get(from)
+                .map_or(0.0, |&event_idx| self._row_height(event_idx));
+        for (pos, &event_idx) in self.events_filtered.iter().enumerate().skip(from) {
+            bottom += self._row_height(event_idx);
+            if y < bottom {
+                return pos;
+            }
+        }
+        self.events_filtered.len().saturating_sub(1)
+    }
+
+    fn _toggle_expanded(&mut self, event_idx: usize) {
+        let Some(record) = self.events.get(event_idx) else {
+            return;
+        };
+        if !self.expanded_rows.remove(&record.id) {
+            self.expanded_rows.insert(record.id);
+        }
+    }
+
+    /// Largest scroll value, where the last row sits at the bottom of the view.
+    fn _max_scroll(&self) -> f64 {
+        let area = self.rows_visible as f32 * self.row_height;
+        let mut used = 0.0;
+        for (pos, &event_idx) in self.events_filtered.iter().enumerate().rev() {
+            let height = self._row_height(event_idx);
+            used += height;
+            if used > area {
+                return pos as f64 + ((used - area) / height) as f64;
+            }
+        }
+        0.0
+    }
+
     fn _scroll(&mut self, change: f64) {
-        // lower limit
-        if (self.scroll_value - change) <= 0.0 {
-            self.scroll_value = 0.0;
+        self.scroll_value = (self.scroll_value - change).clamp(0.0, self._max_scroll());
+    }
+
+    /// Scrolls to `value` (e.g. from the scrollbar) and keeps the highlighted row in view.
+    fn _set_scroll(&mut self, value: f64) {
+        self.scroll_value = value.clamp(0.0, self._max_scroll());
+
+        let first = self.scroll_value.ceil() as usize;
+        let last = (self.scroll_value as usize + self._rows_fitting(self.scroll_value as usize))
+            .saturating_sub(2)
+            .max(first);
+        self.highlighted_scroll_offset = self
+            .highlighted_scroll_offset
+            .clamp(first, last)
+            .min(self.events_filtered.len().saturating_sub(1));
+    }
+
+    /// Top of the table header, below the toolbar and the timeline.
+    fn _table_top(&self) -> f32 {
+        if self.timeline {
+            self.toolbar_height + timeline_view::TIMELINE_HEIGHT
+        } else {
+            self.toolbar_height
+        }
+    }
+
+    fn _update_rows_visible(&mut self) {
+        self.rows_visible = ((self.window_height - (self._table_top() + self.header_height + 30.0))
+            / self.row_height)
+            .max(0.0) as u32;
+    }
+
+    /// Recounts the timeline when the shown rows, its zoom, the window width or the
+    /// severities changed.
+    fn _refresh_timeline(&mut self) {
+        if !self.timeline {
             return;
         }
+        let max_buckets = timeline_view::max_buckets(self.window_width);
+        let key = (
+            self.rows_generation,
+            self.timeline_zoom,
+            max_buckets,
+            self.severity_generation,
+        );
+        if self.histogram_key == Some(key) {
+            return;
+        }
+        self.histogram_key = Some(key);
 
-        // upper limit
-        if (self.events_filtered.len() - self.rows_visible as usize)
-            <= (self.scroll_value - change) as usize
-        {
-            self.scroll_value =
-                (self.events_filtered.len() - self.rows_visible as usize - 1) as f64;
+        let dates = self
+            .events_filtered
+            .iter()
+            .map(|&idx| self.events[idx].date);
+        let range = match self.timeline_zoom {
+            Some(range) => Some(range),
+            None => dates.clone().min().zip(dates.max()),
+        };
+        self.histogram = range.map(|range| {
+            let records = self.events_filtered.iter().map(|&idx| {
+                let record = &self.events[idx];
+                (record.date, self.severity.level_index(record))
+            });
+            Histogram::build(records, range, self.severity.levels.len(), max_buckets)
+        });
+    }
+
+    /// Recomputes the scrollbar minimap when the rows, marks, search hits or severities changed.
+    fn _refresh_minimap(&mut self) {
+        let key = (
+            self.rows_generation,
+            self.selected_rows.generation(),
+            self.search_generation,
+            self.severity_generation,
+        );
+        if self.minimap_key == Some(key) {
             return;
         }
+        self.minimap_key = Some(key);
+        self.minimap.clear();
 
-        self.scroll_value -= change;
+        let len = self.events_filtered.len();
+        if len == 0 {
+            return;
+        }
+        // Rows sharing a pixel or so of the track share one marker
+        let buckets = len.min(MINIMAP_BUCKETS);
+        let bucket_of = |pos: usize| pos * buckets / len;
+        let position = |bucket: usize| bucket as f32 / (buckets - 1).max(1) as f32;
+
+        let mut severity = vec![None; buckets];
+        let mut marks = vec![None; buckets];
+        for (pos, &event_idx) in self.events_filtered.iter().enumerate() {
+            let record = &self.events[event_idx];
+            let bucket = bucket_of(pos);
+            if severity[bucket].is_none()
+                && let Some(level) = self.severity.level_for(record)
+            {
+                severity[bucket] = level.colors(false).0;
+            }
+            if marks[bucket].is_none()
+                && let Some(mark) = self.selected_rows.get(record.id)
+            {
+                marks[bucket] = Some(mark.color.color());
+            }
+        }
+
+        let mut hits = vec![false; buckets];
+        for &pos in self.search_matches.iter().flatten() {
+            hits[bucket_of(pos)] = true;
+        }
+
+        // Later markers are drawn over earlier ones
+        let severity = severity
+            .into_iter()
+            .enumerate()
+            .filter_map(|(bucket, color)| Some((bucket, color?)));
+        let hits = hits
+            .into_iter()
+            .enumerate()
+            .filter(|&(_, hit)| hit)
+            .map(|(bucket, _)| (bucket, SEARCH_MATCH_BG_COLOR));
+        let marks = marks
+            .into_iter()
+            .enumerate()
+            .filter_map(|(bucket, color)| Some((bucket, color?)));
+        self.minimap = severity
+            .chain(hits)
+            .chain(marks)
+            .map(|(bucket, color)| Marker {
+                position: position(bucket),
+                color,
+            })
+            .collect();
     }
 
     fn _scroll_highlight(&mut self, change: f64) {
@@ -372,7 +929,7 @@ impl LogReader {
 
         if (self.events_filtered.len()) <= (self.highlighted_scroll_offset as f64 - change) as usize
         {
-            self.highlighted_scroll_offset = self.events_filtered.len() as usize - 1;
+            self.highlighted_scroll_offset = self.events_filtered.len().saturating_sub(1);
             return;
         }
 
@@ -380,14 +937,45 @@ impl LogReader {
             .highlighted_scroll_offset
             .saturating_sub_signed(change as isize);
         if self.highlighted_scroll_offset
-            >= (self.scroll_value as usize + self.rows_visible as usize + 1)
+            >= (self.scroll_value as usize + self._rows_fitting(self.scroll_value as usize))
+            || self.highlighted_scroll_offset < self.scroll_value as usize
         {
             self._scroll(change);
-        } else if self.highlighted_scroll_offset < self.scroll_value as usize {
-            self._scroll(change);
         }
     }
 
+    /// Cell values of the records in the visible columns, as shown in the table.
+    fn _visible_rows(&self, records: &[&Record]) -> (Vec<Field>, Vec<Vec<String>>) {
+        let fields: Vec<Field> = self.columns.visible().map(|column| column.field).collect();
+        let rows = records
+            .iter()
+            .map(|record| {
+                fields
+                    .iter()
+                    .map(|&field| self.dictionary.display(field, record))
+                    .collect()
+            })
+            .collect();
+        (fields, rows)
+    }
+
+    /// Shift+Arrow: moves the highlight and selects the rows from the anchor to it.
+    fn _extend_selection(&mut self, change: f64) {
+        let anchor_shown = self
+            .selection
+            .anchor()
+            .is_some_and(|anchor| self.events_filtered.contains(&(anchor as usize)));
+        if !anchor_shown
+            && let Some(&event_idx) = self.events_filtered.get(self.highlighted_scroll_offset)
+        {
+            self.selection.select(event_idx as u64);
+        }
+
+        self._scroll_highlight(change);
+        self.selection
+            .extend_to(&self.events_filtered, self.highlighted_scroll_offset);
+    }
+
     fn _get_row_idx_scroll_idx(&self, row_idx: u64) -> Option<u64> {
         if let Some(scroll_idx) = self
             .events_filtered
@@ -400,78 +988,89 @@ impl LogReader {
         None
     }
 
-    fn _get_valid_scrollables(&self) -> Vec<(u64, u64)> {
-        let selected_available = self.selected_rows.iter().filter_map(|&row_id| {
-            if let Some(scroll_id) = self._get_row_idx_scroll_idx(row_id) {
-                Some((scroll_id, row_id))
-            } else {
-                None
-            }
-        });
+    /// The next or previous mark in the shown order, of the chosen color only.
+    fn _next_mark(&self, forward: bool) -> Option<(usize, u64)> {
+        let positions = self
+            .selected_rows
+            .positions(&self.events_filtered, self.mark_color);
+        bookmarks::next_mark(&positions, self.highlighted_scroll_offset, forward)
+    }
 
-        return selected_available.collect();
+    fn _scroll_to(&mut self, scroll_id_offset: u64) {
+        self.scroll_value = scroll_id_offset as f64;
+        self.highlighted_scroll_offset = scroll_id_offset as usize;
+        self._scroll((self.rows_visible as f64 / 2.0).ceil());
     }
 
-    fn _get_next_mark_rev(&self) -> Option<(u64, u64)> {
-        let selected_available: Vec<(u64, u64)> = self._get_valid_scrollables();
+    /// Shows a failed save of a settings file in the toolbar until it is dismissed.
+    pub fn _report_save(&mut self, what: &str, result: io::Result<()>) {
+        if let Err(e) = result {
+            self.status_error = Some(format!("Failed to save {}. {}", what, e));
+        }
+    }
 
-        for (scroll_id, row_idx) in selected_available.clone().into_iter().rev() {
-            if scroll_id >= self.highlighted_scroll_offset as u64 {
-                continue;
-            }
-            return Some((scroll_id, row_idx));
+    fn _history_state(&self) -> HistoryState {
+        HistoryState {
+            filters: self.filters.clone(),
+            highlighted_scroll_offset: self.highlighted_scroll_offset,
+            scroll_value: self.scroll_value,
         }
+    }
 
-        if let Some(last_mark) = selected_available.last() {
-            return Some(*last_mark);
-        } else {
-            None
+    fn _restore_history_state(&mut self, state: HistoryState) {
+        if state.filters != self.filters {
+            self.filters = state.filters;
+            table_view::apply_filter(self);
         }
+
+        let last = self.events_filtered.len().saturating_sub(1);
+        self.highlighted_scroll_offset = state.highlighted_scroll_offset.min(last);
+        self.scroll_value = state.scroll_value.min(last as f64);
     }
 
-    fn _get_next_mark(&self) -> Option<(u64, u64)> {
-        let selected_available: Vec<(u64, u64)> = self._get_valid_scrollables();
+    /// Remembers the current state before the filter named `filter` changes.
+    fn _record_filter_edit(&mut self, filter: &'static str) {
+        self.history.push_edit(self._history_state(), filter);
+    }
 
-        for (scroll_id, row_idx) in selected_available.clone() {
-            if scroll_id <= self.highlighted_scroll_offset as u64 {
-                continue;
-            }
-            return Some((scroll_id, row_idx));
-        }
-        if let Some(first_mark) = selected_available.first() {
-            return Some(*first_mark);
-        } else {
-            None
+    /// Scrolls to `scroll_id_offset` and records the jump in the navigation history.
+    fn _jump_to(&mut self, scroll_id_offset: u64) {
+        self.history.push(self._history_state());
+        self._scroll_to(scroll_id_offset);
+    }
+
+    fn _history_back(&mut self) {
+        if let Some(state) = self.history.back(self._history_state()) {
+            self._restore_history_state(state);
         }
     }
 
-    fn _scroll_to(&mut self, scroll_id_offset: u64) {
-        self.scroll_value = scroll_id_offset as f64;
-        self.highlighted_scroll_offset = scroll_id_offset as usize;
-        self._scroll((self.rows_visible as f64 / 2.0).ceil());
+    fn _history_forward(&mut self) {
+        if let Some(state) = self.history.forward(self._history_state()) {
+            self._restore_history_state(state);
+        }
     }
 
-    fn _add_mark(&mut self, row_id: u64) {
-        self.selected_rows.push(row_id);
-        self.selected_rows.sort();
+    fn _undo_filter(&mut self) {
+        if let Some(state) = self.history.undo(self._history_state()) {
+            self._restore_history_state(state);
+        }
     }
 
-    fn _remove_mark(&mut self, row_idx: u64) {
-        if let Some(idx) = self
-            .selected_rows
-            .iter()
-            .position(|&row_id| row_id == row_idx)
-        {
-            self.selected_rows.remove(idx);
+    fn _redo_filter(&mut self) {
+        if let Some(state) = self.history.redo(self._history_state()) {
+            self._restore_history_state(state);
         }
     }
 
+    fn _add_mark(&mut self, row_id: u64) {
+        self.selected_rows
+            .add(row_id, self.mark_color.unwrap_or_default());
+    }
+
     fn _switch_mark(&mut self, row_idx: u64) {
-        if self.selected_rows.contains(&row_idx) {
-            self._remove_mark(row_idx);
-        } else {
-            self._add_mark(row_idx);
-        }
+        self.selected_rows
+            .toggle(row_idx, self.mark_color.unwrap_or_default());
     }
 
     fn _switch_mark_highlighted_offset(&mut self) {
@@ -483,6 +1082,21 @@ impl LogReader {
         self._switch_mark(*row_idx as u64);
     }
 
+    fn _open_detail(&mut self, event_idx: usize) {
+        if let Some(record) = self.events.get(event_idx) {
+            self.view_detail = Some(ViewDetail::new(record.clone(), self._highlight_settings()));
+        }
+    }
+
+    /// Selected records in the order they are shown, hidden ones are left out.
+    fn _selected_records(&self) -> Vec<&Record> {
+        self.events_filtered
+            .iter()
+            .filter(|&&event_idx| self.selection.contains(event_idx as u64))
+            .map(|&event_idx| &self.events[event_idx])
+            .collect()
+    }
+
     fn _scroll_value_to_row_id(&self, scroll_value: u64) -> u64 {
         if let Some(record) = self.events_filtered.get(scroll_value as usize) {
             self.events.get(*record).unwrap().id
@@ -491,29 +1105,169 @@ impl LogReader {
         }
     }
 
-    fn _find_prev(&mut self) {
-        if let Some(&row_idx) = self.events_filtered.get(self.highlighted_scroll_offset) {
-            if let Some(row) = self.events_filtered.iter().rev().position(|&item| {
-                if row_idx <= item {
-                    return false;
-                }
-                self.events[item].log_message.contains(&self.searching_text)
-            }) {
-                self._scroll_to((self.events_filtered.len() - row - 1) as u64);
+    /// Recompiles the search after its text or options changed.
+    fn _reset_search(&mut self) {
+        self._clear_search_matches();
+        self.search_status.clear();
+        self.search_error = None;
+        self.search_query = None;
+        self.search_results = None;
+
+        if self.searching_text.is_empty() {
+            return;
+        }
+        match SearchQuery::new(&self.searching_text, &self.search_options) {
+            Ok(query) => self.search_query = Some(query),
+            Err(e) => self.search_error = Some(e.to_string()),
+        }
+    }
+
+    fn _highlight_settings(&self) -> HighlightSettings {
+        HighlightSettings {
+            search_text: self.searching_text.clone(),
+            search_options: self.search_options.clone(),
+            filter_terms: self.filters.terms(),
+        }
+    }
+
+    /// Positions in `events_filtered` of the records matching the search, cached until
+    /// the search text, its options or the filtered records change.
+    fn _search_matches(&mut self) -> &[usize] {
+        if self.search_matches.is_none() {
+            let uses_index = self.search_index.is_some()
+                && self
+                    .search_query
+                    .as_ref()
+                    .and_then(SearchQuery::literal)
+                    .is_some();
+            if uses_index && !self.sort_keys.is_empty() {
+                self._update_row_positions();
             }
+            let matches = match &self.search_query {
+                Some(query) => self._find_matches(query),
+                None => vec![],
+            };
+            self.search_matches = Some(matches);
+            self.search_generation += 1;
         }
+
+        self.search_matches.as_deref().unwrap_or_default()
     }
 
-    fn _find_next(&mut self) {
-        if let Some(&row_idx) = self.events_filtered.get(self.highlighted_scroll_offset) {
-            if let Some(row) = self.events_filtered.iter().position(|&item| {
-                if row_idx >= item {
-                    return false;
-                }
-                self.events[item].log_message.contains(&self.searching_text)
-            }) {
-                self._scroll_to(row as u64);
+    /// Drops the cached search hits after the rows or the search changed.
+    pub fn _clear_search_matches(&mut self) {
+        self.search_matches = None;
+        self.search_generation += 1;
+    }
+
+    /// Rebuilds the list of hits shown in the results panel.
+    fn _refresh_search_results(&mut self) {
+        let matches = self._search_matches().to_vec();
+        self.search_results = self.search_query.as_ref().map(|query| {
+            let entries = matches
+                .iter()
+                .map(|&pos| self.events_filtered[pos])
+                .collect();
+            ResultList::new(&self.searching_text, query, &self.events, entries)
+        });
+    }
+
+    fn _update_row_positions(&mut self) {
+        if self
+            .row_positions
+            .as_ref()
+            .is_some_and(|(generation, _)| *generation == self.rows_generation)
+        {
+            return;
+        }
+
+        let mut positions = vec![usize::MAX; self.events.len()];
+        for (pos, &item) in self.events_filtered.iter().enumerate() {
+            positions[item] = pos;
+        }
+        self.row_positions = Some((self.rows_generation, positions));
+    }
+
+    fn _find_matches(&self, query: &SearchQuery) -> Vec<usize> {
+        let candidates = query
+            .literal()
+            .and_then(|literal| self.search_index.as_ref()?.candidates(literal));
+
+        let Some(candidates) = candidates else {
+            return self
+                .events_filtered
+                .iter()
+                .enumerate()
+                .filter(|&(_, &item)| query.is_match(&self.events[item]))
+                .map(|(pos, _)| pos)
+                .collect();
+        };
+
+        // Unsorted rows keep the order of the records, so the hits are found by binary search
+        if self.sort_keys.is_empty() {
+            return candidates
+                .into_iter()
+                .filter_map(|item| self.events_filtered.binary_search(&item).ok())
+                .filter(|&pos| query.is_match(&self.events[self.events_filtered[pos]]))
+                .collect();
+        }
+
+        // Sorted rows look the hits up in the positions built by _update_row_positions
+        let Some((_, positions)) = &self.row_positions else {
+            return vec![];
+        };
+
+        let mut matches: Vec<usize> = candidates
+            .into_iter()
+            .map(|item| positions[item])
+            .filter(|&pos| pos != usize::MAX)
+            .filter(|&pos| query.is_match(&self.events[self.events_filtered[pos]]))
+            .collect();
+        matches.sort_unstable();
+        matches
+    }
+
+    fn _find(&mut self, forward: bool) {
+        let current = self.highlighted_scroll_offset;
+        let matches = self._search_matches();
+        let count = matches.len();
+        if count == 0 {
+            self.search_status = String::from("No matches");
+            return;
+        }
+
+        let next = matches.partition_point(|&pos| pos <= current);
+        let prev = matches.partition_point(|&pos| pos < current);
+        let (idx, wrapped) = if forward {
+            if next < count {
+                (next, false)
+            } else {
+                (0, true)
             }
+        } else if prev > 0 {
+            (prev - 1, false)
+        } else {
+            (count - 1, true)
+        };
+        let target = matches[idx];
+
+        self.search_status = match (wrapped, forward) {
+            (false, _) => format!("match {} of {}", idx + 1, count),
+            (true, true) => format!("match {} of {}, wrapped to top", idx + 1, count),
+            (true, false) => format!("match {} of {}, wrapped to bottom", idx + 1, count),
+        };
+        self._jump_to(target as u64);
+
+        if self.results_panel && self.search_results.is_none() {
+            self._refresh_search_results();
         }
     }
+
+    fn _find_prev(&mut self) {
+        self._find(false);
+    }
+
+    fn _find_next(&mut self) {
+        self._find(true);
+    }
 }
@@ -0,0 +1,144 @@
+use std::{io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::search::SearchOptions;
+use log_reader_core::FilterValues;
+
+const PRESETS_FILE: &str = "presets.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Preset {
+    pub name: String,
+    #[serde(default)]
+    pub filters: FilterValues,
+    #[serde(default)]
+    pub query: String,
+    #[serde(default)]
+    pub search_options: SearchOptions,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Presets {
+    pub presets: Vec<Preset>,
+    pub default: Option<String>,
+}
+
+impl Presets {
+    pub fn load() -> Self {
+        config::load(PRESETS_FILE)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        config::save(PRESETS_FILE, self)
+    }
+
+    pub fn names(&self) -> Vec<String> {
+        self.presets.iter().map(|p| p.name.clone()).collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Preset> {
+        self.presets.iter().find(|p| p.name == name)
+    }
+
+    pub fn default_preset(&self) -> Option<&Preset> {
+        self.get(self.default.as_deref()?)
+    }
+
+    /// Adds the preset, replacing an existing one with the same name.
+    pub fn insert(&mut self, preset: Preset) {
+        match self.presets.iter_mut().find(|p| p.name == preset.name) {
+            Some(existing) => *existing = preset,
+            None => self.presets.push(preset),
+        }
+    }
+
+    /// Fails when there is no preset named `from` or another preset already has the new name.
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), String> {
+        if self.get(from).is_none() {
+            return Err(format!("There is no preset named '{}'.", from));
+        }
+        if from == to {
+            return Ok(());
+        }
+        if self.get(to).is_some() {
+            return Err(format!("A preset named '{}' already exists.", to));
+        }
+        if let Some(preset) = self.presets.iter_mut().find(|p| p.name == from) {
+            preset.name = to.to_owned();
+        }
+        if self.default.as_deref() == Some(from) {
+            self.default = Some(to.to_owned());
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.presets.retain(|p| p.name != name);
+        if self.default.as_deref() == Some(name) {
+            self.default = None;
+        }
+    }
+
+    /// Merges presets from a shared file, returns how many were imported.
+    pub fn import(&mut self, path: &Path) -> io::Result<usize> {
+        let imported: Vec<Preset> = config::read_json(path)?;
+        let count = imported.len();
+        for preset in imported {
+            self.insert(preset);
+        }
+        Ok(count)
+    }
+
+    pub fn export(&self, path: &Path) -> io::Result<()> {
+        config::write_json(path, &self.presets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preset(name: &str) -> Preset {
+        Preset {
+            name: name.to_owned(),
+            ..Preset::default()
+        }
+    }
+
+    #[test]
+    fn test_rename_and_remove_default() {
+        let mut presets = Presets::default();
+        presets.insert(preset("crash"));
+        presets.insert(preset("net"));
+        presets.default = Some("crash".to_owned());
+
+        presets.rename("crash", "crash dump").unwrap();
+        assert_eq!(presets.default.as_deref(), Some("crash dump"));
+
+        presets.remove("crash dump");
+        assert_eq!(presets.names(), vec!["net"]);
+        assert_eq!(presets.default, None);
+    }
+
+    #[test]
+    fn test_rename_to_existing_name() {
+        let mut presets = Presets::default();
+        presets.insert(preset("crash"));
+        presets.insert(preset("net"));
+
+        assert_eq!(
+            presets.rename("crash", "net"),
+            Err(String::from("A preset named 'net' already exists."))
+        );
+        assert_eq!(presets.names(), vec!["crash", "net"]);
+        assert_eq!(presets.rename("net", "net"), Ok(()));
+        assert_eq!(
+            presets.rename("disk", "io"),
+            Err(String::from("There is no preset named 'disk'."))
+        );
+        assert_eq!(presets.names(), vec!["crash", "net"]);
+    }
+}
@@ -0,0 +1,245 @@
+use std::path::Path;
+
+use iced::{
+    Element,
+    Length::Fill,
+    Task, Theme,
+    widget::{button, column, container, pick_list, row, scrollable, text, text_input},
+};
+
+use crate::log_message::{LogReaderMessage, PresetMessage};
+use crate::presets::Preset;
+use crate::{LogReader, table_view};
+
+#[derive(Debug, Default)]
+pub struct PresetsDialog {
+    name: String,
+    path: String,
+    status: String,
+}
+
+pub fn apply_preset(log_reader: &mut LogReader, preset: Preset) {
+    log_reader.history.push(log_reader._history_state());
+    log_reader.filters = preset.filters.clone();
+    log_reader.searching_text = preset.query;
+    log_reader.search_options = preset.search_options;
+    log_reader._reset_search();
+    table_view::apply_filter(log_reader);
+    log_reader.scroll_value = 0.0;
+    log_reader.highlighted_scroll_offset = 0;
+}
+
+/// Name of the preset matching the current filters and search, if any.
+fn active_preset(log_reader: &LogReader) -> Option<String> {
+    let filters = &log_reader.filters;
+    log_reader
+        .presets
+        .presets
+        .iter()
+        .find(|p| {
+            p.filters == *filters
+                && p.query == log_reader.searching_text
+                && p.search_options == log_reader.search_options
+        })
+        .map(|p| p.name.clone())
+}
+
+pub fn picker(log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
+    row![
+        text("Preset"),
+        pick_list(
+            log_reader.presets.names(),
+            active_preset(log_reader),
+            |name| LogReaderMessage::Preset(PresetMessage::Selected(name)),
+        )
+        .placeholder("No preset")
+        .width(250),
+        button("Manage").on_press(LogReaderMessage::Preset(PresetMessage::OpenDialog)),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+pub fn view(log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
+    let dialog = log_reader.presets_dialog.as_ref().unwrap();
+    let msg = LogReaderMessage::Preset;
+
+    let presets = log_reader.presets.presets.iter().map(|preset| {
+        let name = preset.name.clone();
+        let is_default = log_reader.presets.default.as_ref() == Some(&name);
+        let label = if is_default {
+            format!("{} (default)", name)
+        } else {
+            name.clone()
+        };
+
+        row![
+            text(label).width(Fill),
+            button("Load").on_press(msg(PresetMessage::Selected(name.clone()))),
+            button("Rename").on_press_maybe(
+                (!dialog.name.is_empty()).then(|| msg(PresetMessage::Rename(name.clone())))
+            ),
+            if is_default {
+                button("Clear Default").on_press(msg(PresetMessage::ClearDefault))
+            } else {
+                button("Set Default").on_press(msg(PresetMessage::SetDefault(name.clone())))
+            },
+            button("Delete")
+                .style(button::danger)
+                .on_press(msg(PresetMessage::Delete(name))),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center)
+        .into()
+    });
+
+    container(
+        container(
+            column![
+                text("Presets"),
+                row![
+                    text_input("Preset name", &dialog.name)
+                        .on_input(move |txt| msg(PresetMessage::NameInput(txt))),
+                    button("Save Current").on_press_maybe(
+                        (!dialog.name.is_empty()).then(|| msg(PresetMessage::Save))
+                    ),
+                ]
+                .spacing(8),
+                scrollable(column(presets).spacing(4)).height(250),
+                row![
+                    text_input("File path", &dialog.path)
+                        .on_input(move |txt| msg(PresetMessage::PathInput(txt))),
+                    button("Import").on_press(msg(PresetMessage::Import)),
+                    button("Export").on_press(msg(PresetMessage::Export)),
+                ]
+                .spacing(8),
+                row![
+                    text(&dialog.status).width(Fill),
+                    button("Close").on_press(msg(PresetMessage::CloseDialog)),
+                ]
+                .align_y(iced::Alignment::Center),
+            ]
+            .padding(16)
+            .spacing(16),
+        )
+        .width(700)
+        .style(|theme: &Theme| {
+            container::Style::default()
+                .background(theme.palette().background)
+                .border(
+                    iced::Border::default()
+                        .width(2)
+                        .color(theme.palette().success),
+                )
+        }),
+    )
+    .center(Fill)
+    .into()
+}
+
+fn save_presets(log_reader: &mut LogReader) {
+    if let Err(e) = log_reader.presets.save()
+        && let Some(dialog) = log_reader.presets_dialog.as_mut()
+    {
+        dialog.status = format!("Failed to save presets. {}", e);
+    }
+}
+
+pub fn update(log_reader: &mut LogReader, msg: LogReaderMessage) -> Task<LogReaderMessage> {
+    let msg = match msg {
+        LogReaderMessage::Preset(msg) => msg,
+        LogReaderMessage::Event(iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+            key: iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape),
+            ..
+        })) => PresetMessage::CloseDialog,
+        _ => return Task::none(),
+    };
+
+    match msg {
+        PresetMessage::Selected(name) => {
+            if let Some(preset) = log_reader.presets.get(&name).cloned() {
+                apply_preset(log_reader, preset);
+            }
+        }
+        PresetMessage::OpenDialog => {
+            log_reader.presets_dialog = Some(PresetsDialog {
+                name: active_preset(log_reader).unwrap_or_default(),
+                ..PresetsDialog::default()
+            });
+        }
+        PresetMessage::CloseDialog => {
+            log_reader.presets_dialog = None;
+        }
+        PresetMessage::NameInput(txt) => {
+            if let Some(dialog) = log_reader.presets_dialog.as_mut() {
+                dialog.name = txt;
+            }
+        }
+        PresetMessage::PathInput(txt) => {
+            if let Some(dialog) = log_reader.presets_dialog.as_mut() {
+                dialog.path = txt;
+            }
+        }
+        PresetMessage::Save => {
+            let Some(dialog) = log_reader.presets_dialog.as_ref() else {
+                return Task::none();
+            };
+            let preset = Preset {
+                name: dialog.name.clone(),
+                filters: log_reader.filters.clone(),
+                query: log_reader.searching_text.clone(),
+                search_options: log_reader.search_options.clone(),
+            };
+            log_reader.presets.insert(preset);
+            save_presets(log_reader);
+        }
+        PresetMessage::Rename(name) => {
+            let Some(dialog) = log_reader.presets_dialog.as_ref() else {
+                return Task::none();
+            };
+            let new_name = dialog.name.clone();
+            let status = match log_reader.presets.rename(&name, &new_name) {
+                Ok(()) => format!("Renamed '{}' to '{}'.", name, new_name),
+                Err(e) => e,
+            };
+            if let Some(dialog) = log_reader.presets_dialog.as_mut() {
+                dialog.status = status;
+            }
+            save_presets(log_reader);
+        }
+        PresetMessage::Delete(name) => {
+            log_reader.presets.remove(&name);
+            save_presets(log_reader);
+        }
+        PresetMessage::SetDefault(name) => {
+            log_reader.presets.default = Some(name);
+            save_presets(log_reader);
+        }
+        PresetMessage::ClearDefault => {
+            log_reader.presets.default = None;
+            save_presets(log_reader);
+        }
+        PresetMessage::Import => {
+            let Some(dialog) = log_reader.presets_dialog.as_mut() else {
+                return Task::none();
+            };
+            dialog.status = match log_reader.presets.import(Path::new(&dialog.path)) {
+                Ok(count) => format!("Imported {} presets.", count),
+                Err(e) => format!("Import failed. {}", e),
+            };
+            save_presets(log_reader);
+        }
+        PresetMessage::Export => {
+            let Some(dialog) = log_reader.presets_dialog.as_mut() else {
+                return Task::none();
+            };
+            dialog.status = match log_reader.presets.export(Path::new(&dialog.path)) {
+                Ok(()) => format!("Exported {} presets.", log_reader.presets.presets.len()),
+                Err(e) => format!("Export failed. {}", e),
+            };
+        }
+    }
+
+    Task::none()
+}
@@ -0,0 +1,326 @@
+use std::{collections::BTreeMap, fmt};
+
+use chrono::TimeDelta;
+use log_reader_core::{DATE_FORMAT, Record};
+
+use crate::bookmarks::Bookmark;
+use crate::export::html_escape;
+use crate::severity::SeverityScheme;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReportFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl ReportFormat {
+    pub const ALL: [ReportFormat; 2] = [ReportFormat::Markdown, ReportFormat::Html];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Markdown => "md",
+            ReportFormat::Html => "html",
+        }
+    }
+}
+
+impl fmt::Display for ReportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReportFormat::Markdown => "Markdown",
+            ReportFormat::Html => "HTML",
+        })
+    }
+}
+
+/// One marked record of the timeline with the records logged around it.
+struct Entry<'a> {
+    record: &'a Record,
+    note: &'a str,
+    level: Option<&'a str>,
+    /// Time since the previous marked record
+    delta: Option<TimeDelta>,
+    context: &'a [Record],
+}
+
+/// Records per module in the time span of the marks, counted for each severity level.
+struct Summary<'a> {
+    levels: Vec<&'a str>,
+    modules: Vec<(&'a str, Vec<usize>)>,
+}
+
+/// Like `+1h 02m 03.456s`, leaving out the zero leading units.
+fn format_delta(delta: TimeDelta) -> String {
+    let ms = delta.num_milliseconds();
+    let sign = if ms < 0 { "-" } else { "+" };
+    let ms = ms.unsigned_abs();
+    let (hours, minutes, seconds) = (ms / 3_600_000, ms / 60_000 % 60, ms % 60_000);
+    let seconds = format!("{}.{:03}s", seconds / 1000, seconds % 1000);
+    match (hours, minutes) {
+        (0, 0) => format!("{}{}", sign, seconds),
+        (0, _) => format!("{}{}m {:0>7}", sign, minutes, seconds),
+        _ => format!("{}{}h {:02}m {:0>7}", sign, hours, minutes, seconds),
+    }
+}
+
+fn summarize<'a>(
+    events: &'a [Record],
+    entries: &[Entry],
+    severity: &'a SeverityScheme,
+) -> Summary<'a> {
+    let levels: Vec<&str> = severity.levels.iter().map(|l| l.name.as_str()).collect();
+    let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
+        return Summary {
+            levels,
+            modules: vec![],
+        };
+    };
+    let (start, end) = (first.record.date, last.record.date);
+
+    let mut counts: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for record in events.iter().filter(|r| r.date >= start && r.date <= end) {
+        let Some(level) = severity.level_for(record) else {
+            continue;
+        };
+        let idx = levels.iter().position(|&name| name == level.name).unwrap();
+        counts
+            .entry(&record.module_name)
+            .or_insert_with(|| vec![0; levels.len()])[idx] += 1;
+    }
+
+    let mut modules: Vec<(&str, Vec<usize>)> = counts.into_iter().collect();
+    modules.sort_by_key(|(_, counts)| std::cmp::Reverse(counts.iter().sum::<usize>()));
+    Summary { levels, modules }
+}
+
+/// Builds a post-mortem report of the marked records, in time order, with `context`
+/// records of the log before and after each.
+pub fn build(
+    format: ReportFormat,
+    title: &str,
+    events: &[Record],
+    marks: &[Bookmark],
+    context: usize,
+    severity: &SeverityScheme,
+) -> String {
+    let mut marked: Vec<(&Record, &str)> = marks
+        .iter()
+        .filter_map(|mark| Some((events.get(mark.id as usize)?, mark.note.as_str())))
+        .collect();
+    marked.sort_by_key(|(record, _)| (record.date, record.id));
+
+    let mut previous = None;
+    let entries: Vec<Entry> = marked
+        .into_iter()
+        .map(|(record, note)| {
+            let idx = record.id as usize;
+            let entry = Entry {
+                record,
+                note,
+                level: severity.level_for(record).map(|l| l.name.as_str()),
+                delta: previous.map(|date| record.date - date),
+                context: &events
+                    [idx.saturating_sub(context)..(idx + context + 1).min(events.len())],
+            };
+            previous = Some(record.date);
+            entry
+        })
+        .collect();
+    let summary = summarize(events, &entries, severity);
+
+    match format {
+        ReportFormat::Markdown => markdown(title, &entries, &summary),
+        ReportFormat::Html => html(title, &entries, &summary),
+    }
+}
+
+fn context_line(record: &Record, marked: &Record) -> String {
+    format!(
+        "{} {} {} {}",
+        if record.id == marked.id { ">" } else { " " },
+        record.date.format(DATE_FORMAT),
+        record.module_name,
+        record.log_message.replace(['\r', '\n'], " ")
+    )
+}
+
+fn span_text(entries: &[Entry]) -> String {
+    match (entries.first(), entries.last()) {
+        (Some(first), Some(last)) => format!(
+            "{} to {} ({})",
+            first.record.date.format(DATE_FORMAT),
+            last.record.date.format(DATE_FORMAT),
+            format_delta(last.record.date - first.record.date)
+        ),
+        _ => String::from("No marked records"),
+    }
+}
+
+fn markdown(title: &str, entries: &[Entry], summary: &Summary) -> String {
+    let cell = |text: &str| text.replace('|', "\\|").replace(['\r', '\n'], " ");
+    let mut out = format!("# Incident report: {}\n\n", title);
+    out += &format!("**Time span:** {}\n\n", span_text(entries));
+
+    out += "## Timeline\n\n| # | Time | Delta | Level | Module | Message | Note |\n";
+    out += "| --- | --- | --- | --- | --- | --- | --- |\n";
+    for (n, entry) in entries.iter().enumerate() {
+        out += &format!(
+            "| {} | {} | {} | {} | {} | {} | {} |\n",
+            n + 1,
+            entry.record.date.format(DATE_FORMAT),
+            entry.delta.map(format_delta).unwrap_or_default(),
+            entry.level.unwrap_or_default(),
+            cell(&entry.record.module_name),
+            cell(&entry.record.log_message),
+            cell(entry.note),
+        );
+    }
+
+    out += "\n## Details\n";
+    for (n, entry) in entries.iter().enumerate() {
+        out += &format!(
+            "\n### {}. Record {} at {}\n\n",
+            n + 1,
+            entry.record.id,
+            entry.record.date.format(DATE_FORMAT)
+        );
+        if !entry.note.is_empty() {
+            out += &format!("{}\n\n", entry.note);
+        }
+        out += "```\n";
+        for record in entry.context {
+            out += &context_line(record, entry.record);
+            out += "\n";
+        }
+        out += "```\n";
+    }
+
+    out += "\n## Records per module in the time span\n\n";
+    out += &format!("| Module | {} |\n", summary.levels.join(" | "));
+    out += &format!("| --- |{}\n", " ---: |".repeat(summary.levels.len()));
+    for (module, counts) in &summary.modules {
+        let counts: Vec<String> = counts.iter().map(usize::to_string).collect();
+        out += &format!("| {} | {} |\n", cell(module), counts.join(" | "));
+    }
+    out
+}
+
+fn html(title: &str, entries: &[Entry], summary: &Summary) -> String {
+    let esc = html_escape;
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Incident report: {0}</title>\n<style>\n\
+         body {{ font-family: sans-serif; }}\n\
+         table {{ border-collapse: collapse; }}\n\
+         th, td {{ border: 1px solid #ccc; padding: 2px 6px; text-align: left; vertical-align: top; }}\n\
+         pre {{ background: #f4f4f4; padding: 6px; }}\n</style>\n</head>\n<body>\n\
+         <h1>Incident report: {0}</h1>\n<p><b>Time span:</b> {1}</p>\n",
+        esc(title),
+        esc(&span_text(entries))
+    );
+
+    out += "<h2>Timeline</h2>\n<table>\n<tr><th>#</th><th>Time</th><th>Delta</th><th>Level</th>\
+            <th>Module</th><th>Message</th><th>Note</th></tr>\n";
+    for (n, entry) in entries.iter().enumerate() {
+        out += &format!(
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            n + 1,
+            entry.record.date.format(DATE_FORMAT),
+            entry.delta.map(format_delta).unwrap_or_default(),
+            esc(entry.level.unwrap_or_default()),
+            esc(&entry.record.module_name),
+            esc(&entry.record.log_message),
+            esc(entry.note),
+        );
+    }
+    out += "</table>\n<h2>Details</h2>\n";
+    for (n, entry) in entries.iter().enumerate() {
+        out += &format!(
+            "<h3>{}. Record {} at {}</h3>\n",
+            n + 1,
+            entry.record.id,
+            entry.record.date.format(DATE_FORMAT)
+        );
+        if !entry.note.is_empty() {
+            out += &format!("<p>{}</p>\n", esc(entry.note));
+        }
+        let lines: Vec<String> = entry
+            .context
+            .iter()
+            .map(|record| esc(&context_line(record, entry.record)))
+            .collect();
+        out += &format!("<pre>{}</pre>\n", lines.join("\n"));
+    }
+
+    out += "<h2>Records per module in the time span</h2>\n<table>\n<tr><th>Module</th>";
+    for level in &summary.levels {
+        out += &format!("<th>{}</th>", esc(level));
+    }
+    out += "</tr>\n";
+    for (module, counts) in &summary.modules {
+        out += &format!("<tr><td>{}</td>", esc(module));
+        for count in counts {
+            out += &format!("<td>{}</td>", count);
+        }
+        out += "</tr>\n";
+    }
+    out += "</table>\n</body>\n</html>\n";
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log_reader_core::testing;
+
+    #[test]
+    fn test_markdown_report() {
+        let template = testing::record();
+        let events: Vec<Record> = (0..10)
+            .map(|id| Record {
+                id,
+                date: template.date + TimeDelta::seconds(id as i64 * 30),
+                module_name: String::from(if id % 2 == 0 { "Net" } else { "Disk" }),
+                facility: if id < 5 { 4 } else { 3 },
+                ..template.clone()
+            })
+            .collect();
+        let marks = vec![
+            Bookmark {
+                id: 7,
+                note: String::from("recovered"),
+                color: Default::default(),
+            },
+            Bookmark {
+                id: 1,
+                note: String::from("first | failure"),
+                color: Default::default(),
+            },
+        ];
+
+        let report = build(
+            ReportFormat::Markdown,
+            "app.log",
+            &events,
+            &marks,
+            1,
+            &SeverityScheme::default(),
+        );
+
+        // Time order, the delta to the previous mark and escaped notes
+        let rows: Vec<&str> = report.lines().filter(|l| l.starts_with("| 1 |")).collect();
+        assert!(rows[0].contains("first \\| failure"));
+        assert!(report.contains("| +3m 00.000s |"));
+        assert!(report.contains("(+3m 00.000s)"));
+
+        // One record before and after each mark, the marked one pointed at
+        assert_eq!(report.lines().filter(|l| l.starts_with('>')).count(), 2);
+        assert_eq!(report.matches("```").count(), 4);
+
+        // Records 1 to 7 by module, the busiest first
+        assert!(report.contains("| Module | Warning | Error |"));
+        assert!(report.contains("| Disk | 2 | 2 |"));
+        assert!(report.contains("| Net | 1 | 2 |"));
+    }
+}
@@ -0,0 +1,172 @@
+use std::path::Path;
+
+use iced::{
+    Element,
+    Length::Fill,
+    Task, Theme,
+    widget::{button, column, container, pick_list, row, text, text_input},
+};
+
+use crate::LogReader;
+use crate::bookmarks::Bookmark;
+use crate::log_message::{LogReaderMessage, ReportMessage};
+use crate::report::{self, ReportFormat};
+
+const DEFAULT_CONTEXT: &str = "3";
+
+#[derive(Debug, Default)]
+pub struct ReportDialog {
+    format: ReportFormat,
+    /// Records shown before and after each marked one
+    context: String,
+    path: String,
+    status: String,
+}
+
+fn default_path(log_file_name: &str, format: ReportFormat) -> String {
+    let path = Path::new(log_file_name);
+    let stem = path.file_stem().map_or(String::from("log"), |stem| {
+        stem.to_string_lossy().into_owned()
+    });
+    path.with_file_name(format!("{}-incident.{}", stem, format.extension()))
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn build(log_reader: &LogReader, dialog: &ReportDialog) -> String {
+    let marks: Vec<Bookmark> = log_reader.selected_rows.iter().cloned().collect();
+    report::build(
+        dialog.format,
+        &log_reader.current_log_file_name,
+        &log_reader.events,
+        &marks,
+        dialog.context.parse().unwrap_or(0),
+        &log_reader.severity,
+    )
+}
+
+pub fn view(log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
+    let dialog = log_reader.report_dialog.as_ref().unwrap();
+    let msg = LogReaderMessage::Report;
+    let has_marks = !log_reader.selected_rows.is_empty();
+
+    container(
+        container(
+            column![
+                text("Incident Report"),
+                text(format!(
+                    "A timeline of the {} marked records with their notes, the records around \
+                     each and the counts per module and severity between the first and the last.",
+                    log_reader.selected_rows.len()
+                ))
+                .size(12),
+                row![
+                    text("Format").width(80),
+                    pick_list(ReportFormat::ALL, Some(dialog.format), move |format| msg(
+                        ReportMessage::Format(format)
+                    )),
+                    text("Context records"),
+                    text_input(DEFAULT_CONTEXT, &dialog.context)
+                        .on_input(move |txt| msg(ReportMessage::ContextInput(txt)))
+                        .width(60),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+                row![
+                    text("File").width(80),
+                    text_input("File path", &dialog.path)
+                        .on_input(move |txt| msg(ReportMessage::PathInput(txt))),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+                row![
+                    text(&dialog.status).width(Fill),
+                    button("Copy").on_press_maybe(has_marks.then(|| msg(ReportMessage::Copy))),
+                    button("Save").on_press_maybe(
+                        (has_marks && !dialog.path.is_empty()).then(|| msg(ReportMessage::Save))
+                    ),
+                    button("Close").on_press(msg(ReportMessage::CloseDialog)),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+            ]
+            .padding(16)
+            .spacing(16),
+        )
+        .width(700)
+        .style(|theme: &Theme| {
+            container::Style::default()
+                .background(theme.palette().background)
+                .border(
+                    iced::Border::default()
+                        .width(2)
+                        .color(theme.palette().success),
+                )
+        }),
+    )
+    .center(Fill)
+    .into()
+}
+
+pub fn update(log_reader: &mut LogReader, msg: LogReaderMessage) -> Task<LogReaderMessage> {
+    let msg = match msg {
+        LogReaderMessage::Report(msg) => msg,
+        LogReaderMessage::Event(iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+            key: iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape),
+            ..
+        })) => ReportMessage::CloseDialog,
+        _ => return Task::none(),
+    };
+
+    if let ReportMessage::OpenDialog = msg {
+        let format = ReportFormat::default();
+        log_reader.report_dialog = Some(ReportDialog {
+            format,
+            context: DEFAULT_CONTEXT.to_owned(),
+            path: default_path(&log_reader.current_log_file_name, format),
+            status: String::default(),
+        });
+        return Task::none();
+    }
+
+    let log_file_name = log_reader.current_log_file_name.clone();
+    let Some(dialog) = log_reader.report_dialog.as_mut() else {
+        return Task::none();
+    };
+
+    match msg {
+        ReportMessage::OpenDialog => (),
+        ReportMessage::CloseDialog => log_reader.report_dialog = None,
+        ReportMessage::Format(format) => {
+            if dialog.path == default_path(&log_file_name, dialog.format) {
+                dialog.path = default_path(&log_file_name, format);
+            }
+            dialog.format = format;
+        }
+        ReportMessage::ContextInput(txt) => {
+            if txt.chars().all(|c| c.is_ascii_digit()) {
+                dialog.context = txt;
+            }
+        }
+        ReportMessage::PathInput(txt) => dialog.path = txt,
+        ReportMessage::Copy => {
+            let dialog = log_reader.report_dialog.as_ref().unwrap();
+            let report = build(log_reader, dialog);
+            if let Some(dialog) = log_reader.report_dialog.as_mut() {
+                dialog.status = String::from("Copied to the clipboard");
+            }
+            return iced::clipboard::write(report);
+        }
+        ReportMessage::Save => {
+            let dialog = log_reader.report_dialog.as_ref().unwrap();
+            let status = match std::fs::write(&dialog.path, build(log_reader, dialog)) {
+                Ok(()) => format!("Saved to {}", dialog.path),
+                Err(e) => format!("Failed to save the report. {}", e),
+            };
+            if let Some(dialog) = log_reader.report_dialog.as_mut() {
+                dialog.status = status;
+            }
+        }
+    }
+    Task::none()
+}
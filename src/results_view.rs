@@ -0,0 +1,206 @@
+use iced::{
+    Element,
+    Length::Fill,
+    Theme,
+    widget::{Column, Row, button, column, container, row, scrollable, text},
+};
+use log_reader_core::Record;
+
+use crate::LogReader;
+use crate::log_message::{LogReaderMessage, ResultsMessage};
+use crate::search::SearchQuery;
+
+// Only the first hits get a snippet and a row in the panel, the count still covers all of them
+const RESULTS_SHOWN_LIMIT: usize = 1000;
+const SNIPPET_BEFORE: usize = 30;
+const SNIPPET_LENGTH: usize = 100;
+pub const PANEL_WIDTH: f32 = 360.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dock {
+    Left,
+    #[default]
+    Right,
+}
+
+/// All records matching one search, kept as event indexes so pinned lists survive filter changes.
+#[derive(Debug, Clone)]
+pub struct ResultList {
+    title: String,
+    entries: Vec<usize>,
+    snippets: Vec<String>,
+}
+
+impl ResultList {
+    pub fn new(title: &str, query: &SearchQuery, events: &[Record], entries: Vec<usize>) -> Self {
+        let snippets = entries
+            .iter()
+            .take(RESULTS_SHOWN_LIMIT)
+            .map(|&idx| snippet(query, &events[idx]))
+            .collect();
+
+        Self {
+            title: title.to_owned(),
+            entries,
+            snippets,
+        }
+    }
+}
+
+fn snippet(query: &SearchQuery, record: &Record) -> String {
+    let message = &record.log_message;
+    let (start, prefix) = match query.find_ranges(message).first() {
+        Some(range) if range.start > SNIPPET_BEFORE => (
+            message.floor_char_boundary(range.start - SNIPPET_BEFORE),
+            "\u{2026}",
+        ),
+        _ => (0, ""),
+    };
+    let end = message.floor_char_boundary(start + SNIPPET_LENGTH);
+    let suffix = if end < message.len() { "\u{2026}" } else { "" };
+
+    format!("{}{}{}", prefix, &message[start..end], suffix).replace(['\r', '\n'], " ")
+}
+
+fn result_list<'a>(
+    log_reader: &'a LogReader,
+    list: &'a ResultList,
+    pinned: Option<usize>,
+) -> Element<'a, LogReaderMessage> {
+    let msg = LogReaderMessage::Results;
+
+    let action = match pinned {
+        Some(idx) => button("Unpin").on_press(msg(ResultsMessage::Unpin(idx))),
+        None => button("Pin").on_press(msg(ResultsMessage::Pin)),
+    };
+
+    let entries = list
+        .entries
+        .iter()
+        .zip(list.snippets.iter())
+        .map(|(&event_idx, snippet)| {
+            let record = &log_reader.events[event_idx];
+            button(
+                row![
+                    text(record.id).width(60),
+                    text(snippet).wrapping(text::Wrapping::None),
+                ]
+                .spacing(8),
+            )
+            .style(button::text)
+            .width(Fill)
+            .on_press(msg(ResultsMessage::Jump(event_idx)))
+            .into()
+        });
+
+    let mut entries = Column::with_children(entries);
+    if list.entries.len() > list.snippets.len() {
+        entries = entries.push(text(format!(
+            "Showing the first {} of {} hits",
+            list.snippets.len(),
+            list.entries.len()
+        )));
+    }
+
+    container(
+        column![
+            row![
+                text(format!("{} ({})", list.title, list.entries.len())).width(Fill),
+                action,
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+            scrollable(entries).height(Fill),
+        ]
+        .spacing(8),
+    )
+    .padding(8)
+    .width(PANEL_WIDTH)
+    .height(Fill)
+    .style(|theme: &Theme| {
+        container::Style::default()
+            .background(theme.extended_palette().background.weak.color)
+            .border(
+                iced::Border::default()
+                    .width(1)
+                    .color(theme.palette().success),
+            )
+    })
+    .into()
+}
+
+pub fn view(log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
+    let current: Element<'_, LogReaderMessage> = match &log_reader.search_results {
+        Some(list) => result_list(log_reader, list, None),
+        None => container(text(
+            "Press Enter in the search window (/) or F3 to list the hits.",
+        ))
+        .padding(8)
+        .width(PANEL_WIDTH)
+        .into(),
+    };
+
+    let pinned = log_reader
+        .pinned_results
+        .iter()
+        .enumerate()
+        .map(|(idx, list)| result_list(log_reader, list, Some(idx)));
+
+    let dock_label = match log_reader.results_dock {
+        Dock::Left => "Dock Right",
+        Dock::Right => "Dock Left",
+    };
+
+    column![
+        row![
+            text("Search Results").width(Fill),
+            button(dock_label).on_press(LogReaderMessage::Results(ResultsMessage::SwitchDock)),
+            button("Close").on_press(LogReaderMessage::Results(ResultsMessage::Toggle)),
+        ]
+        .spacing(8)
+        .padding(4)
+        .align_y(iced::Alignment::Center),
+        scrollable(Row::with_children(std::iter::once(current).chain(pinned)).spacing(4))
+            .direction(scrollable::Direction::Horizontal(
+                scrollable::Scrollbar::default()
+            ))
+            .height(Fill),
+    ]
+    .width(iced::Shrink)
+    .max_width(PANEL_WIDTH * 3.0)
+    .into()
+}
+
+pub fn update(log_reader: &mut LogReader, msg: ResultsMessage) {
+    match msg {
+        ResultsMessage::Toggle => {
+            log_reader.results_panel = !log_reader.results_panel;
+            if log_reader.results_panel {
+                log_reader._refresh_search_results();
+            }
+        }
+        ResultsMessage::SwitchDock => {
+            log_reader.results_dock = match log_reader.results_dock {
+                Dock::Left => Dock::Right,
+                Dock::Right => Dock::Left,
+            };
+        }
+        ResultsMessage::Pin => {
+            if let Some(list) = log_reader.search_results.clone() {
+                log_reader.pinned_results.push(list);
+            }
+        }
+        ResultsMessage::Unpin(idx) => {
+            if idx < log_reader.pinned_results.len() {
+                log_reader.pinned_results.remove(idx);
+            }
+        }
+        ResultsMessage::Jump(event_idx) => {
+            if let Some(scroll_idx) =
+                log_reader._get_row_idx_scroll_idx(log_reader.events[event_idx].id)
+            {
+                log_reader._jump_to(scroll_idx);
+            }
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use std::{borrow::Cow, io};
+
+use iced::Color;
+use log_reader_core::{Field, Record};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+const RULES_FILE: &str = "highlight_rules.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleOp {
+    Equals,
+    Contains,
+    Matches,
+}
+
+impl RuleOp {
+    pub const ALL: [RuleOp; 3] = [RuleOp::Equals, RuleOp::Contains, RuleOp::Matches];
+}
+
+impl std::fmt::Display for RuleOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            RuleOp::Equals => "==",
+            RuleOp::Contains => "contains",
+            RuleOp::Matches => "matches",
+        })
+    }
+}
+
+/// Colors rows whose field matches a pattern, without filtering anything out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighlightRule {
+    pub enabled: bool,
+    pub field: Field,
+    pub op: RuleOp,
+    pub pattern: String,
+    /// Hex colors like `#8040c0`, empty keeps the row's color
+    pub background: String,
+    pub text_color: String,
+
+    #[serde(skip)]
+    regex: Option<Regex>,
+    #[serde(skip)]
+    background_color: Option<Color>,
+    #[serde(skip)]
+    text_color_value: Option<Color>,
+}
+
+impl Default for HighlightRule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            field: Field::LogMessage,
+            op: RuleOp::Contains,
+            pattern: String::default(),
+            background: String::default(),
+            text_color: String::default(),
+            regex: None,
+            background_color: None,
+            text_color_value: None,
+        }
+    }
+}
+
+impl HighlightRule {
+    /// Has to be called after any of the public fields changed.
+    pub fn compile(&mut self) {
+        self.regex = match self.op {
+            RuleOp::Matches => Regex::new(&self.pattern).ok(),
+            _ => None,
+        };
+        self.background_color = self.background.parse().ok();
+        self.text_color_value = self.text_color.parse().ok();
+    }
+
+    pub fn pattern_error(&self) -> bool {
+        self.op == RuleOp::Matches && self.regex.is_none()
+    }
+
+    pub fn background_color(&self) -> Option<Color> {
+        self.background_color
+    }
+
+    pub fn text_color(&self) -> Option<Color> {
+        self.text_color_value
+    }
+
+    pub fn matches(&self, record: &Record) -> bool {
+        if !self.enabled || self.pattern.is_empty() {
+            return false;
+        }
+
+        // Text fields are compared in place, only the numbers and the date are formatted
+        let value = match self.field {
+            Field::ComputerName => Cow::Borrowed(record.computer_name.as_str()),
+            Field::ProcessUser => Cow::Borrowed(record.process_user.as_str()),
+            Field::ModuleName => Cow::Borrowed(record.module_name.as_str()),
+            Field::LogMessage => Cow::Borrowed(record.log_message.as_str()),
+            field => Cow::Owned(field.value(record)),
+        };
+        match self.op {
+            RuleOp::Equals => *value == *self.pattern,
+            RuleOp::Contains => value.contains(&self.pattern),
+            RuleOp::Matches => self.regex.as_ref().is_some_and(|re| re.is_match(&value)),
+        }
+    }
+}
+
+/// Rules ordered by priority, the first one in the list wins.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HighlightRules {
+    pub rules: Vec<HighlightRule>,
+}
+
+impl HighlightRules {
+    pub fn load() -> Self {
+        let mut rules: Self = config::load(RULES_FILE);
+        rules.rules.iter_mut().for_each(HighlightRule::compile);
+        rules
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        config::save(RULES_FILE, self)
+    }
+
+    /// Background and text color of the record, each taken from the first matching rule
+    /// that sets it.
+    pub fn colors(&self, record: &Record) -> (Option<Color>, Option<Color>) {
+        let mut background = None;
+        let mut text_color = None;
+
+        for rule in &self.rules {
+            if (background.is_some() || rule.background_color.is_none())
+                && (text_color.is_some() || rule.text_color_value.is_none())
+            {
+                continue;
+            }
+            if rule.matches(record) {
+                background = background.or(rule.background_color);
+                text_color = text_color.or(rule.text_color_value);
+            }
+        }
+
+        (background, text_color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log_reader_core::testing;
+
+    fn record(module_name: &str, log_message: &str) -> Record {
+        Record {
+            module_name: String::from(module_name),
+            log_message: String::from(log_message),
+            ..testing::record()
+        }
+    }
+
+    fn rule(
+        field: Field,
+        op: RuleOp,
+        pattern: &str,
+        background: &str,
+        text: &str,
+    ) -> HighlightRule {
+        let mut rule = HighlightRule {
+            field,
+            op,
+            pattern: pattern.to_owned(),
+            background: background.to_owned(),
+            text_color: text.to_owned(),
+            ..HighlightRule::default()
+        };
+        rule.compile();
+        rule
+    }
+
+    #[test]
+    fn test_priority() {
+        let rules = HighlightRules {
+            rules: vec![
+                rule(
+                    Field::LogMessage,
+                    RuleOp::Matches,
+                    "dead(lock)?",
+                    "#800080",
+                    "",
+                ),
+                rule(
+                    Field::ModuleName,
+                    RuleOp::Equals,
+                    "Net",
+                    "#ff0000",
+                    "#0000ff",
+                ),
+            ],
+        };
+
+        let purple = "#800080".parse().ok();
+        let red = "#ff0000".parse().ok();
+        let blue = "#0000ff".parse().ok();
+
+        assert_eq!(rules.colors(&record("Net", "deadlock")), (purple, blue));
+        assert_eq!(rules.colors(&record("Net", "ok")), (red, blue));
+        assert_eq!(rules.colors(&record("Network", "ok")), (None, None));
+    }
+}
@@ -0,0 +1,173 @@
+use iced::{
+    Element,
+    Length::Fill,
+    Task, Theme,
+    widget::{
+        Column, button, checkbox, column, container, pick_list, row, scrollable, text, text_input,
+    },
+};
+use log_reader_core::Field;
+
+use crate::LogReader;
+use crate::log_message::{LogReaderMessage, RuleMessage};
+use crate::rules::{HighlightRule, RuleOp};
+
+pub fn color_input<'a>(
+    placeholder: &'a str,
+    value: &'a str,
+    on_input: impl Fn(String) -> LogReaderMessage + 'a,
+) -> Element<'a, LogReaderMessage> {
+    let valid = value.is_empty() || value.parse::<iced::Color>().is_ok();
+
+    text_input(placeholder, value)
+        .on_input(on_input)
+        .width(110)
+        .style(move |theme: &Theme, status| {
+            let mut style = text_input::default(theme, status);
+            if !valid {
+                style.border.color = theme.palette().danger;
+            }
+            style
+        })
+        .into()
+}
+
+fn rule_row(idx: usize, rule: &HighlightRule) -> Element<'_, LogReaderMessage> {
+    let msg = LogReaderMessage::Rule;
+
+    let (background, text_color) = (rule.background_color(), rule.text_color());
+    let preview = container(text("Sample"))
+        .padding([2, 6])
+        .style(move |_theme: &Theme| container::Style {
+            background: background.map(iced::Background::Color),
+            text_color,
+            ..container::Style::default()
+        });
+
+    let pattern_error = rule.pattern_error();
+
+    row![
+        checkbox(rule.enabled).on_toggle(move |enabled| msg(RuleMessage::Enabled(idx, enabled))),
+        pick_list(Field::ALL, Some(rule.field), move |field| msg(
+            RuleMessage::Field(idx, field)
+        ))
+        .width(140),
+        pick_list(RuleOp::ALL, Some(rule.op), move |op| msg(RuleMessage::Op(
+            idx, op
+        )))
+        .width(110),
+        text_input("Pattern", &rule.pattern)
+            .on_input(move |txt| msg(RuleMessage::Pattern(idx, txt)))
+            .style(move |theme: &Theme, status| {
+                let mut style = text_input::default(theme, status);
+                if pattern_error {
+                    style.border.color = theme.palette().danger;
+                }
+                style
+            }),
+        color_input("Background", &rule.background, move |txt| msg(
+            RuleMessage::Background(idx, txt)
+        )),
+        color_input("Text", &rule.text_color, move |txt| msg(
+            RuleMessage::TextColor(idx, txt)
+        )),
+        preview,
+        button("\u{2191}").on_press(msg(RuleMessage::MoveUp(idx))),
+        button("\u{2193}").on_press(msg(RuleMessage::MoveDown(idx))),
+        button("Delete")
+            .style(button::danger)
+            .on_press(msg(RuleMessage::Delete(idx))),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+pub fn view(log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
+    let rules = Column::with_children(
+        log_reader
+            .rules
+            .rules
+            .iter()
+            .enumerate()
+            .map(|(idx, rule)| rule_row(idx, rule)),
+    )
+    .spacing(4);
+
+    container(
+        container(
+            column![
+                text("Highlight Rules"),
+                text(
+                    "Rules higher in the list win. Marks and the highlighted row are drawn over rule colors."
+                )
+                .size(12),
+                scrollable(rules).height(350),
+                row![
+                    button("Add Rule").on_press(LogReaderMessage::Rule(RuleMessage::Add)),
+                    iced::widget::space().width(Fill),
+                    button("Close").on_press(LogReaderMessage::Rule(RuleMessage::CloseDialog)),
+                ],
+            ]
+            .padding(16)
+            .spacing(16),
+        )
+        .width(1100)
+        .style(|theme: &Theme| {
+            container::Style::default()
+                .background(theme.palette().background)
+                .border(
+                    iced::Border::default()
+                        .width(2)
+                        .color(theme.palette().success),
+                )
+        }),
+    )
+    .center(Fill)
+    .into()
+}
+
+pub fn update(log_reader: &mut LogReader, msg: LogReaderMessage) -> Task<LogReaderMessage> {
+    let msg = match msg {
+        LogReaderMessage::Rule(msg) => msg,
+        LogReaderMessage::Event(iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+            key: iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape),
+            ..
+        })) => RuleMessage::CloseDialog,
+        _ => return Task::none(),
+    };
+
+    let rules = &mut log_reader.rules.rules;
+    match msg {
+        RuleMessage::OpenDialog => {
+            log_reader.rules_dialog = true;
+        }
+        RuleMessage::CloseDialog => {
+            log_reader.rules_dialog = false;
+            let saved = log_reader.rules.save();
+            log_reader._report_save("highlight rules", saved);
+        }
+        RuleMessage::Add => rules.push(HighlightRule::default()),
+        RuleMessage::Delete(idx) if idx < rules.len() => {
+            rules.remove(idx);
+        }
+        RuleMessage::MoveUp(idx) if idx > 0 && idx < rules.len() => rules.swap(idx - 1, idx),
+        RuleMessage::MoveDown(idx) if idx + 1 < rules.len() => rules.swap(idx, idx + 1),
+        RuleMessage::Enabled(idx, enabled) => edit_rule(rules, idx, |r| r.enabled = enabled),
+        RuleMessage::Field(idx, field) => edit_rule(rules, idx, |r| r.field = field),
+        RuleMessage::Op(idx, op) => edit_rule(rules, idx, |r| r.op = op),
+        RuleMessage::Pattern(idx, txt) => edit_rule(rules, idx, |r| r.pattern = txt),
+        RuleMessage::Background(idx, txt) => edit_rule(rules, idx, |r| r.background = txt),
+        RuleMessage::TextColor(idx, txt) => edit_rule(rules, idx, |r| r.text_color = txt),
+        _ => (),
+    }
+
+    Task::none()
+}
+
+fn edit_rule(rules: &mut [HighlightRule], idx: usize, edit: impl FnOnce(&mut HighlightRule)) {
+    if let Some(rule) = rules.get_mut(idx) {
+        edit(rule);
+        rule.compile();
+    }
+}
@@ -0,0 +1,264 @@
+use iced::advanced::widget::{Tree, tree};
+use iced::advanced::{Clipboard, Layout, Shell, Widget, layout, mouse, renderer};
+use iced::{Background, Border, Color, Element, Event, Length, Rectangle, Size, Theme};
+
+const MIN_THUMB_HEIGHT: f32 = 20.0;
+const MARKER_HEIGHT: f32 = 2.0;
+
+/// A colored tick on the track, at `position` from 0 (top) to 1 (bottom).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Marker {
+    pub position: f32,
+    pub color: Color,
+}
+
+/// Vertical scrollbar for a list of `total` rows of which `visible` fit in the view, `value`
+/// being the first row shown and `max` the largest value, given by the list as its rows may
+/// differ in height. The thumb is sized by the visible part, clicking the track pages and
+/// the thumb can be dragged. Markers draw a minimap along the track.
+pub struct ScrollBar<'a, Message> {
+    total: f64,
+    visible: f64,
+    max: f64,
+    value: f64,
+    markers: &'a [Marker],
+    on_scroll: Box<dyn Fn(f64) -> Message + 'a>,
+    width: f32,
+}
+
+pub fn scroll_bar<'a, Message>(
+    total: f64,
+    visible: f64,
+    max: f64,
+    value: f64,
+    markers: &'a [Marker],
+    on_scroll: impl Fn(f64) -> Message + 'a,
+) -> ScrollBar<'a, Message> {
+    ScrollBar {
+        total,
+        visible,
+        max,
+        value,
+        markers,
+        on_scroll: Box::new(on_scroll),
+        width: 12.0,
+    }
+}
+
+impl<Message> ScrollBar<'_, Message> {
+    pub fn width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    fn thumb(&self, track: Rectangle) -> Rectangle {
+        if self.max <= 0.0 {
+            return track;
+        }
+
+        let height = (track.height * (self.visible / self.total).min(1.0) as f32)
+            .max(MIN_THUMB_HEIGHT)
+            .min(track.height);
+        let progress = (self.value / self.max).clamp(0.0, 1.0) as f32;
+
+        Rectangle {
+            y: track.y + (track.height - height) * progress,
+            height,
+            ..track
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Cursor y and value when the thumb was grabbed
+    drag: Option<(f32, f64)>,
+}
+
+impl<Message> Widget<Message, Theme, iced::Renderer> for ScrollBar<'_, Message> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size {
+            width: Length::Fixed(self.width),
+            height: Length::Fill,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _tree: &mut Tree,
+        _renderer: &iced::Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        layout::atomic(limits, self.width, Length::Fill)
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &iced::Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+        let track = layout.bounds();
+        let thumb = self.thumb(track);
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let Some(position) = cursor.position_over(track) else {
+                    return;
+                };
+
+                if thumb.contains(position) {
+                    state.drag = Some((position.y, self.value));
+                } else if position.y < thumb.y {
+                    shell.publish((self.on_scroll)(self.value - self.visible));
+                } else {
+                    shell.publish((self.on_scroll)(self.value + self.visible));
+                }
+                shell.capture_event();
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                let Some((start_y, start_value)) = state.drag else {
+                    return;
+                };
+
+                let free_space = track.height - thumb.height;
+                if free_space > 0.0 {
+                    let moved = ((position.y - start_y) / free_space) as f64;
+                    shell.publish((self.on_scroll)(start_value + moved * self.max));
+                }
+                shell.capture_event();
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+                if state.drag.is_some() =>
+            {
+                state.drag = None;
+                shell.capture_event();
+            }
+            _ => (),
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        _renderer: &iced::Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+        if state.drag.is_some() {
+            mouse::Interaction::Grabbing
+        } else if cursor.is_over(self.thumb(layout.bounds())) {
+            mouse::Interaction::Grab
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut iced::Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+    ) {
+        use iced::advanced::Renderer as _;
+
+        let track = layout.bounds();
+        let palette = theme.extended_palette();
+
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: track,
+                ..renderer::Quad::default()
+            },
+            Background::Color(palette.background.weak.color),
+        );
+
+        for marker in self.markers {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds: Rectangle {
+                        y: track.y + (track.height - MARKER_HEIGHT) * marker.position,
+                        height: MARKER_HEIGHT,
+                        ..track
+                    },
+                    ..renderer::Quad::default()
+                },
+                Background::Color(marker.color),
+            );
+        }
+
+        let dragging = tree.state.downcast_ref::<State>().drag.is_some();
+        let thumb_color = if dragging {
+            palette.primary.strong.color
+        } else {
+            palette.background.strongest.color
+        };
+        renderer.fill_quad(
+            renderer::Quad {
+                bounds: self.thumb(track).shrink(2),
+                border: Border::default().rounded(4),
+                ..renderer::Quad::default()
+            },
+            Background::Color(Color {
+                a: 0.7,
+                ..thumb_color
+            }),
+        );
+    }
+}
+
+impl<'a, Message: 'a> From<ScrollBar<'a, Message>> for Element<'a, Message> {
+    fn from(scroll_bar: ScrollBar<'a, Message>) -> Self {
+        Element::new(scroll_bar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumb() {
+        let track = Rectangle::new(iced::Point::ORIGIN, Size::new(12.0, 100.0));
+
+        let bar = scroll_bar(1000.0, 10.0, 990.0, 990.0, &[], |_| ());
+        let thumb = bar.thumb(track);
+        assert_eq!(thumb.height, MIN_THUMB_HEIGHT);
+        assert_eq!(thumb.y + thumb.height, track.height);
+
+        let bar = scroll_bar(40.0, 10.0, 30.0, 15.0, &[], |_| ());
+        assert_eq!(
+            bar.thumb(track),
+            Rectangle::new([0.0, 37.5].into(), Size::new(12.0, 25.0))
+        );
+
+        // Expanded rows leave room for fewer rows, the end is reached at the given max
+        let bar = scroll_bar(40.0, 10.0, 35.0, 35.0, &[], |_| ());
+        let thumb = bar.thumb(track);
+        assert_eq!(thumb.y + thumb.height, track.height);
+
+        // Fewer rows than fit in the view
+        let bar = scroll_bar(3.0, 10.0, 0.0, 0.0, &[], |_| ());
+        assert_eq!(bar.thumb(track), track);
+    }
+}
@@ -0,0 +1,217 @@
+use std::ops::Range;
+
+use log_reader_core::{Field, Record};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+/// Which columns the search looks at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchScope {
+    All,
+    Field(Field),
+}
+
+impl SearchScope {
+    pub fn options() -> Vec<SearchScope> {
+        std::iter::once(SearchScope::All)
+            .chain(Field::ALL.into_iter().map(SearchScope::Field))
+            .collect()
+    }
+}
+
+impl std::fmt::Display for SearchScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SearchScope::All => f.write_str("All Columns"),
+            SearchScope::Field(field) => field.fmt(f),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchOptions {
+    pub regex: bool,
+    pub case_insensitive: bool,
+    pub whole_word: bool,
+    pub scope: SearchScope,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            regex: false,
+            case_insensitive: false,
+            whole_word: false,
+            scope: SearchScope::Field(Field::LogMessage),
+        }
+    }
+}
+
+/// Compiled form of the search text and options.
+#[derive(Debug, Clone)]
+pub struct SearchQuery {
+    regex: Regex,
+    scope: SearchScope,
+    // Plain text that every match contains, used to narrow the records with the index
+    literal: Option<String>,
+}
+
+impl SearchQuery {
+    pub fn new(text: &str, options: &SearchOptions) -> Result<Self, regex::Error> {
+        let mut pattern = if options.regex {
+            text.to_owned()
+        } else {
+            regex::escape(text)
+        };
+        if options.whole_word {
+            pattern = format!(r"\b(?:{})\b", pattern);
+        }
+
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(options.case_insensitive)
+            .build()?;
+
+        // The index folds only ASCII letters, other letters ignoring case need a full scan
+        let literal = (!options.regex
+            && options.scope == SearchScope::Field(Field::LogMessage)
+            && (!options.case_insensitive || text.is_ascii()))
+        .then(|| text.to_owned());
+
+        Ok(Self {
+            regex,
+            scope: options.scope,
+            literal,
+        })
+    }
+
+    pub fn scope(&self) -> SearchScope {
+        self.scope
+    }
+
+    pub fn literal(&self) -> Option<&str> {
+        self.literal.as_deref()
+    }
+
+    pub fn is_match(&self, record: &Record) -> bool {
+        match self.scope {
+            SearchScope::Field(Field::LogMessage) => self.regex.is_match(&record.log_message),
+            SearchScope::Field(field) => self.regex.is_match(&field.value(record)),
+            SearchScope::All => Field::ALL
+                .iter()
+                .any(|field| self.regex.is_match(&field.value(record))),
+        }
+    }
+
+    /// Byte ranges of all non-empty matches in `text`.
+    pub fn find_ranges(&self, text: &str) -> Vec<Range<usize>> {
+        self.regex
+            .find_iter(text)
+            .filter(|m| !m.is_empty())
+            .map(|m| m.range())
+            .collect()
+    }
+
+    /// Byte ranges of all non-empty matches in `text`, each with the non-empty ranges of the
+    /// capture groups that took part in it.
+    pub fn find_groups(&self, text: &str) -> Vec<(Range<usize>, Vec<Range<usize>>)> {
+        self.regex
+            .captures_iter(text)
+            .filter_map(|captures| {
+                let whole = captures.get(0).filter(|m| !m.is_empty())?.range();
+                let groups = captures
+                    .iter()
+                    .skip(1)
+                    .flatten()
+                    .filter(|m| !m.is_empty())
+                    .map(|m| m.range())
+                    .collect();
+                Some((whole, groups))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log_reader_core::testing;
+
+    fn record(module_name: &str, log_message: &str) -> Record {
+        Record {
+            module_name: String::from(module_name),
+            log_message: String::from(log_message),
+            ..testing::record()
+        }
+    }
+
+    #[test]
+    fn test_literal() {
+        let case_insensitive = SearchOptions {
+            case_insensitive: true,
+            ..SearchOptions::default()
+        };
+        let literal = |text: &str, options: &SearchOptions| {
+            SearchQuery::new(text, options)
+                .unwrap()
+                .literal()
+                .map(str::to_owned)
+        };
+        assert_eq!(
+            literal("timeout", &case_insensitive).as_deref(),
+            Some("timeout")
+        );
+        assert_eq!(
+            literal("ÄRGER", &SearchOptions::default()).as_deref(),
+            Some("ÄRGER")
+        );
+
+        let query = SearchQuery::new("ÄRGER", &case_insensitive).unwrap();
+        assert_eq!(query.literal(), None);
+        assert!(query.is_match(&record("Net", "Viel ärger mit dem Netz")));
+    }
+
+    #[test]
+    fn test_options() {
+        let rec = record("Net", "Connection TIMEOUT after 30s");
+
+        let plain = SearchOptions::default();
+        assert!(!SearchQuery::new("timeout", &plain).unwrap().is_match(&rec));
+
+        let case_insensitive = SearchOptions {
+            case_insensitive: true,
+            ..SearchOptions::default()
+        };
+        assert!(
+            SearchQuery::new("timeout", &case_insensitive)
+                .unwrap()
+                .is_match(&rec)
+        );
+
+        let whole_word = SearchOptions {
+            whole_word: true,
+            ..SearchOptions::default()
+        };
+        assert!(
+            !SearchQuery::new("TIME", &whole_word)
+                .unwrap()
+                .is_match(&rec)
+        );
+
+        let regex = SearchOptions {
+            regex: true,
+            ..SearchOptions::default()
+        };
+        let query = SearchQuery::new(r"\d+s", &regex).unwrap();
+        assert!(query.is_match(&rec));
+        assert_eq!(query.find_ranges(&rec.log_message), vec![25..28]);
+        assert!(SearchQuery::new("(", &regex).is_err());
+
+        let all = SearchOptions {
+            scope: SearchScope::All,
+            ..SearchOptions::default()
+        };
+        assert!(SearchQuery::new("Net", &all).unwrap().is_match(&rec));
+        assert!(!SearchQuery::new("Net", &plain).unwrap().is_match(&rec));
+    }
+}
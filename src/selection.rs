@@ -0,0 +1,100 @@
+use std::collections::BTreeSet;
+
+/// Rows picked with the mouse or Shift+Arrow, kept by record ID so they survive filtering
+/// and sorting. The anchor is where a Shift range starts.
+#[derive(Debug, Default)]
+pub struct Selection {
+    ids: BTreeSet<u64>,
+    anchor: Option<u64>,
+}
+
+impl Selection {
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn contains(&self, id: u64) -> bool {
+        self.ids.contains(&id)
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.ids.iter().copied()
+    }
+
+    pub fn anchor(&self) -> Option<u64> {
+        self.anchor
+    }
+
+    pub fn clear(&mut self) {
+        self.ids.clear();
+        self.anchor = None;
+    }
+
+    /// Plain click: only `id` is selected.
+    pub fn select(&mut self, id: u64) {
+        self.ids.clear();
+        self.ids.insert(id);
+        self.anchor = Some(id);
+    }
+
+    /// Ctrl-click: adds or removes `id` and starts the next range there.
+    pub fn toggle(&mut self, id: u64) {
+        if !self.ids.remove(&id) {
+            self.ids.insert(id);
+        }
+        self.anchor = Some(id);
+    }
+
+    /// Shift-click: selects the rows shown between the anchor and the row at `pos` of
+    /// `rows`. Without an anchor in view only that row is selected.
+    pub fn extend_to(&mut self, rows: &[usize], pos: usize) {
+        let Some(&target) = rows.get(pos) else {
+            return;
+        };
+        let anchor_pos = self
+            .anchor
+            .and_then(|anchor| rows.iter().position(|&row| row as u64 == anchor));
+        let Some(anchor_pos) = anchor_pos else {
+            self.select(target as u64);
+            return;
+        };
+
+        self.ids = rows[anchor_pos.min(pos)..=anchor_pos.max(pos)]
+            .iter()
+            .map(|&row| row as u64)
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_click_selection() {
+        // Sorted rows, so a range follows the shown order and not the IDs
+        let rows = [4, 2, 7, 1, 9];
+        let mut selection = Selection::default();
+
+        selection.select(2);
+        selection.extend_to(&rows, 3);
+        assert_eq!(selection.ids().collect::<Vec<_>>(), vec![1, 2, 7]);
+
+        // The anchor stays, so the range can shrink back past it
+        selection.extend_to(&rows, 0);
+        assert_eq!(selection.ids().collect::<Vec<_>>(), vec![2, 4]);
+
+        selection.toggle(9);
+        selection.toggle(4);
+        assert_eq!(selection.ids().collect::<Vec<_>>(), vec![2, 9]);
+        assert_eq!(selection.anchor(), Some(4));
+
+        // An anchor filtered out of view starts over
+        selection.extend_to(&[1, 9], 0);
+        assert_eq!(selection.ids().collect::<Vec<_>>(), vec![1]);
+    }
+}
@@ -0,0 +1,138 @@
+use std::{collections::HashMap, io, path::Path};
+
+use log_reader_core::{FilterValues, Record};
+use serde::{Deserialize, Serialize};
+
+use crate::bookmarks::Bookmark;
+use crate::config;
+use crate::search::SearchOptions;
+
+const SESSIONS_FILE: &str = "sessions.json";
+// Sessions of the least recently closed logs are dropped beyond this
+const SESSION_LIMIT: usize = 100;
+
+/// What is restored when a log is opened again.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub marks: Vec<Bookmark>,
+    pub filters: FilterValues,
+    pub search_text: String,
+    pub search_options: SearchOptions,
+    pub scroll_value: f64,
+    pub highlighted_scroll_offset: usize,
+    /// Seconds since the epoch, to tell which sessions are the oldest
+    pub saved_at: i64,
+    /// Number of records and date of the first one, to tell whether the file is still the
+    /// same log
+    pub records: usize,
+    pub first_record: Option<i64>,
+}
+
+impl Session {
+    pub fn new(events: &[Record]) -> Self {
+        Self {
+            records: events.len(),
+            first_record: events.first().map(|record| record.date.timestamp_millis()),
+            saved_at: chrono::Utc::now().timestamp(),
+            ..Self::default()
+        }
+    }
+
+    /// A log that grew since the session was saved still fits it, one that got shorter or
+    /// starts with another record was replaced.
+    pub fn fits(&self, events: &[Record]) -> bool {
+        let first = events.first().map(|record| record.date.timestamp_millis());
+        events.len() >= self.records && (self.first_record.is_none() || self.first_record == first)
+    }
+}
+
+/// Sessions are stored by the absolute path of the log.
+pub fn session_key(file_name: &str) -> String {
+    let path = Path::new(file_name);
+    path.canonicalize()
+        .unwrap_or_else(|_| path.to_path_buf())
+        .to_string_lossy()
+        .into_owned()
+}
+
+pub fn load(key: &str) -> Option<Session> {
+    let mut sessions: HashMap<String, Session> = config::load(SESSIONS_FILE);
+    sessions.remove(key)
+}
+
+pub fn save(key: &str, session: Session) -> io::Result<()> {
+    let mut sessions: HashMap<String, Session> = config::load(SESSIONS_FILE);
+    sessions.insert(key.to_owned(), session);
+    prune(&mut sessions, SESSION_LIMIT);
+    config::save(SESSIONS_FILE, &sessions)
+}
+
+fn prune(sessions: &mut HashMap<String, Session>, limit: usize) {
+    if sessions.len() <= limit {
+        return;
+    }
+    let mut saved: Vec<i64> = sessions.values().map(|session| session.saved_at).collect();
+    saved.sort_unstable_by(|a, b| b.cmp(a));
+    let oldest_kept = saved[limit - 1];
+    sessions.retain(|_, session| session.saved_at >= oldest_kept);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log_reader_core::testing;
+
+    #[test]
+    fn test_fits() {
+        let events: Vec<Record> = (0..3)
+            .map(|id| Record {
+                id,
+                ..testing::record()
+            })
+            .collect();
+        let session = Session::new(&events[..2]);
+
+        assert!(session.fits(&events[..2]));
+        assert!(session.fits(&events));
+        assert!(!session.fits(&events[..1]));
+
+        let replaced: Vec<Record> = events
+            .iter()
+            .map(|record| Record {
+                date: record.date + chrono::TimeDelta::hours(1),
+                ..record.clone()
+            })
+            .collect();
+        assert!(!session.fits(&replaced));
+
+        // Sessions saved before the records were counted are kept
+        assert!(Session::default().fits(&events[..1]));
+    }
+
+    #[test]
+    fn test_prune_oldest() {
+        let mut sessions: HashMap<String, Session> = (0..5)
+            .map(|saved_at| {
+                (
+                    saved_at.to_string(),
+                    Session {
+                        saved_at,
+                        ..Session::default()
+                    },
+                )
+            })
+            .collect();
+
+        prune(&mut sessions, 3);
+        let mut kept: Vec<&str> = sessions.keys().map(String::as_str).collect();
+        kept.sort();
+        assert_eq!(kept, vec!["2", "3", "4"]);
+
+        let json = serde_json::to_string(&sessions["4"]).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Session>(&json).unwrap(),
+            sessions["4"]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use std::io;
+
+use iced::Color;
+use log_reader_core::{Field, Record};
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+const SEVERITY_FILE: &str = "severity.json";
+
+/// A named severity for one value of the scheme's field, with colors for light and dark themes.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeverityLevel {
+    pub name: String,
+    pub value: String,
+    /// Hex colors like `#f9e154`, empty keeps the theme's color
+    pub light_background: String,
+    pub light_text: String,
+    pub dark_background: String,
+    pub dark_text: String,
+
+    #[serde(skip)]
+    colors: [Option<Color>; 4],
+}
+
+impl SeverityLevel {
+    fn new(name: &str, value: &str, light: (&str, &str), dark: (&str, &str)) -> Self {
+        let mut level = Self {
+            name: name.to_owned(),
+            value: value.to_owned(),
+            light_background: light.0.to_owned(),
+            light_text: light.1.to_owned(),
+            dark_background: dark.0.to_owned(),
+            dark_text: dark.1.to_owned(),
+            colors: [None; 4],
+        };
+        level.compile();
+        level
+    }
+
+    /// Has to be called after any of the color fields changed.
+    pub fn compile(&mut self) {
+        self.colors = [
+            self.light_background.parse().ok(),
+            self.light_text.parse().ok(),
+            self.dark_background.parse().ok(),
+            self.dark_text.parse().ok(),
+        ];
+    }
+
+    /// Background and text color for a light or dark theme.
+    pub fn colors(&self, is_dark: bool) -> (Option<Color>, Option<Color>) {
+        if is_dark {
+            (self.colors[2], self.colors[3])
+        } else {
+            (self.colors[0], self.colors[1])
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SeverityScheme {
+    pub field: Field,
+    pub levels: Vec<SeverityLevel>,
+}
+
+impl Default for SeverityScheme {
+    fn default() -> Self {
+        Self {
+            field: Field::Facility,
+            levels: vec![
+                SeverityLevel::new(
+                    "Warning",
+                    "3",
+                    ("#a38c06", "#ffffff"),
+                    ("#f9e154", "#000000"),
+                ),
+                SeverityLevel::new("Error", "4", ("#f02c2c", "#000000"), ("#f02c2c", "#000000")),
+            ],
+        }
+    }
+}
+
+impl SeverityScheme {
+    pub fn load() -> Self {
+        let mut scheme: Self = config::load(SEVERITY_FILE);
+        scheme.levels.iter_mut().for_each(SeverityLevel::compile);
+        scheme
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        config::save(SEVERITY_FILE, self)
+    }
+
+    pub fn level_for(&self, record: &Record) -> Option<&SeverityLevel> {
+        self.levels.get(self.level_index(record)?)
+    }
+
+    /// Position of the record's level in `levels`.
+    pub fn level_index(&self, record: &Record) -> Option<usize> {
+        if self.levels.is_empty() {
+            return None;
+        }
+        let value = self.field.value(record);
+        self.levels.iter().position(|level| level.value == value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log_reader_core::testing;
+
+    #[test]
+    fn test_level_for() {
+        let mut record = Record {
+            module_name: String::from("Net"),
+            log_level: 2,
+            facility: 4,
+            ..testing::record()
+        };
+
+        let mut scheme = SeverityScheme::default();
+        assert_eq!(scheme.level_for(&record).unwrap().name, "Error");
+
+        scheme.field = Field::LogLevel;
+        assert!(scheme.level_for(&record).is_none());
+        record.log_level = 3;
+        let level = scheme.level_for(&record).unwrap();
+        assert_eq!(level.name, "Warning");
+        assert_eq!(level.colors(false).0, "#a38c06".parse().ok());
+    }
+}
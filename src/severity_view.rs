@@ -0,0 +1,162 @@
+use iced::{
+    Element,
+    Length::Fill,
+    Task, Theme,
+    widget::{Column, button, column, container, pick_list, row, scrollable, text, text_input},
+};
+use log_reader_core::Field;
+
+use crate::LogReader;
+use crate::log_message::{LogReaderMessage, SeverityMessage};
+use crate::rules_view::color_input;
+use crate::severity::SeverityLevel;
+
+fn preview<'a>(level: &SeverityLevel, is_dark: bool) -> Element<'a, LogReaderMessage> {
+    let (background, text_color) = level.colors(is_dark);
+    container(text(if is_dark { "Dark" } else { "Light" }))
+        .padding([2, 6])
+        .style(move |_theme: &Theme| container::Style {
+            background: background.map(iced::Background::Color),
+            text_color,
+            ..container::Style::default()
+        })
+        .into()
+}
+
+fn level_row(idx: usize, level: &SeverityLevel) -> Element<'_, LogReaderMessage> {
+    let msg = LogReaderMessage::Severity;
+
+    row![
+        text_input("Name", &level.name)
+            .on_input(move |txt| msg(SeverityMessage::Name(idx, txt)))
+            .width(130),
+        text_input("Value", &level.value)
+            .on_input(move |txt| msg(SeverityMessage::Value(idx, txt)))
+            .width(90),
+        color_input("Light bg", &level.light_background, move |txt| msg(
+            SeverityMessage::LightBackground(idx, txt)
+        )),
+        color_input("Light text", &level.light_text, move |txt| msg(
+            SeverityMessage::LightText(idx, txt)
+        )),
+        preview(level, false),
+        color_input("Dark bg", &level.dark_background, move |txt| msg(
+            SeverityMessage::DarkBackground(idx, txt)
+        )),
+        color_input("Dark text", &level.dark_text, move |txt| msg(
+            SeverityMessage::DarkText(idx, txt)
+        )),
+        preview(level, true),
+        button("Delete")
+            .style(button::danger)
+            .on_press(msg(SeverityMessage::Delete(idx))),
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+pub fn view(log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
+    let msg = LogReaderMessage::Severity;
+    let scheme = &log_reader.severity;
+
+    let levels = Column::with_children(
+        scheme
+            .levels
+            .iter()
+            .enumerate()
+            .map(|(idx, level)| level_row(idx, level)),
+    )
+    .spacing(4);
+
+    container(
+        container(
+            column![
+                text("Severity Colors"),
+                row![
+                    text("Severity is read from"),
+                    pick_list(Field::ALL, Some(scheme.field), move |field| msg(
+                        SeverityMessage::Field(field)
+                    )),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+                scrollable(levels).height(300),
+                row![
+                    button("Add Severity").on_press(msg(SeverityMessage::Add)),
+                    iced::widget::space().width(Fill),
+                    button("Close").on_press(msg(SeverityMessage::CloseDialog)),
+                ],
+            ]
+            .padding(16)
+            .spacing(16),
+        )
+        .width(1100)
+        .style(|theme: &Theme| {
+            container::Style::default()
+                .background(theme.palette().background)
+                .border(
+                    iced::Border::default()
+                        .width(2)
+                        .color(theme.palette().success),
+                )
+        }),
+    )
+    .center(Fill)
+    .into()
+}
+
+pub fn update(log_reader: &mut LogReader, msg: LogReaderMessage) -> Task<LogReaderMessage> {
+    let msg = match msg {
+        LogReaderMessage::Severity(msg) => msg,
+        LogReaderMessage::Event(iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+            key: iced::keyboard::Key::Named(iced::keyboard::key::Named::Escape),
+            ..
+        })) => SeverityMessage::CloseDialog,
+        _ => return Task::none(),
+    };
+
+    let edits = !matches!(
+        msg,
+        SeverityMessage::OpenDialog | SeverityMessage::CloseDialog
+    );
+    let levels = &mut log_reader.severity.levels;
+    match msg {
+        SeverityMessage::OpenDialog => {
+            log_reader.severity_dialog = true;
+        }
+        SeverityMessage::CloseDialog => {
+            log_reader.severity_dialog = false;
+            let saved = log_reader.severity.save();
+            log_reader._report_save("severity colors", saved);
+        }
+        SeverityMessage::Field(field) => log_reader.severity.field = field,
+        SeverityMessage::Add => levels.push(SeverityLevel::default()),
+        SeverityMessage::Delete(idx) if idx < levels.len() => {
+            levels.remove(idx);
+        }
+        SeverityMessage::Name(idx, txt) => edit_level(levels, idx, |l| l.name = txt),
+        SeverityMessage::Value(idx, txt) => edit_level(levels, idx, |l| l.value = txt),
+        SeverityMessage::LightBackground(idx, txt) => {
+            edit_level(levels, idx, |l| l.light_background = txt)
+        }
+        SeverityMessage::LightText(idx, txt) => edit_level(levels, idx, |l| l.light_text = txt),
+        SeverityMessage::DarkBackground(idx, txt) => {
+            edit_level(levels, idx, |l| l.dark_background = txt)
+        }
+        SeverityMessage::DarkText(idx, txt) => edit_level(levels, idx, |l| l.dark_text = txt),
+        _ => (),
+    }
+    if edits {
+        log_reader.severity_generation += 1;
+    }
+
+    Task::none()
+}
+
+fn edit_level(levels: &mut [SeverityLevel], idx: usize, edit: impl FnOnce(&mut SeverityLevel)) {
+    if let Some(level) = levels.get_mut(idx) {
+        edit(level);
+        level.compile();
+    }
+}
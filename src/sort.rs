@@ -0,0 +1,98 @@
+use std::cmp::Ordering;
+
+use log_reader_core::{Field, Record};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortKey {
+    pub field: Field,
+    pub descending: bool,
+}
+
+/// Header click: sorts by `field` ascending, then descending, then not at all.
+/// With `add` the other keys are kept and `field` is added as the last one.
+pub fn toggle_sort(keys: &mut Vec<SortKey>, field: Field, add: bool) {
+    let existing = keys.iter().position(|key| key.field == field);
+
+    if !add {
+        let descending = match (existing, keys.len()) {
+            (Some(idx), 1) if keys[idx].descending => {
+                keys.clear();
+                return;
+            }
+            (Some(_), 1) => true,
+            _ => false,
+        };
+        *keys = vec![SortKey { field, descending }];
+        return;
+    }
+
+    match existing {
+        Some(idx) if keys[idx].descending => {
+            keys.remove(idx);
+        }
+        Some(idx) => keys[idx].descending = true,
+        None => keys.push(SortKey {
+            field,
+            descending: false,
+        }),
+    }
+}
+
+pub fn compare(keys: &[SortKey], a: &Record, b: &Record) -> Ordering {
+    keys.iter()
+        .map(|key| {
+            let ordering = key.field.compare(a, b);
+            if key.descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Sorts the indexes into `events`, records equal in all keys keep the order of the log.
+pub fn sort_indexes(keys: &[SortKey], events: &[Record], indexes: &mut [usize]) {
+    indexes.sort_by(|&a, &b| compare(keys, &events[a], &events[b]).then(a.cmp(&b)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log_reader_core::testing;
+
+    #[test]
+    fn test_multi_key_sort() {
+        let events: Vec<Record> = [(3, "b"), (4, "a"), (3, "a"), (4, "a")]
+            .into_iter()
+            .enumerate()
+            .map(|(id, (log_level, module_name))| Record {
+                id: id as u64,
+                log_level,
+                module_name: module_name.to_owned(),
+                ..testing::record()
+            })
+            .collect();
+
+        let mut keys = vec![];
+        toggle_sort(&mut keys, Field::LogLevel, false);
+        toggle_sort(&mut keys, Field::LogLevel, false);
+        toggle_sort(&mut keys, Field::ModuleName, true);
+        assert_eq!(keys.len(), 2);
+        assert!(keys[0].descending && !keys[1].descending);
+
+        let mut indexes = vec![0, 1, 2, 3];
+        sort_indexes(&keys, &events, &mut indexes);
+        assert_eq!(indexes, vec![1, 3, 2, 0]);
+
+        // A plain click drops the secondary keys, two more clicks go back to the log's order
+        toggle_sort(&mut keys, Field::LogLevel, false);
+        assert_eq!(keys.len(), 1);
+        toggle_sort(&mut keys, Field::LogLevel, false);
+        toggle_sort(&mut keys, Field::LogLevel, false);
+        assert!(keys.is_empty());
+        sort_indexes(&keys, &events, &mut indexes);
+        assert_eq!(indexes, vec![0, 1, 2, 3]);
+    }
+}
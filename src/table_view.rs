@@ -1,22 +1,39 @@
-use std::{ops::Add, str::FromStr};
-
-use chrono::{DateTime, Local};
 use iced::{
     Element, Event as IcedEvent, Font,
     Length::Fill,
-    Task, Theme, Vector, color, font, keyboard, mouse,
+    Task, Theme, Vector, color, font, keyboard, mouse, never,
     widget::{
-        Column, Container, Row, column, container, mouse_area, operation::focus, row, slider,
-        stack, text, text_editor, text_input, vertical_slider,
+        Column, Container, Row, button, checkbox, column, container, mouse_area, operation::focus,
+        pick_list, rich_text, row, scrollable, span, stack, text, text_input, tooltip,
     },
-    window,
 };
+use log_reader_core::{Field, FilterMode, Record, filter};
 
-use crate::record::Record;
-use crate::{LogColumn, MyFilter, log_message::LogReaderMessage};
+use crate::bookmarks_view;
+use crate::columns::{ColumnDrag, ColumnLayout};
+use crate::context_menu::{self, MenuTarget};
+use crate::copy::{self, CopyFormat};
+use crate::export::ExportScope;
+use crate::export_view;
+use crate::highlight::{
+    FILTER_MATCH_BG_COLOR, GROUP_MATCH_BG_COLOR, MATCH_TXT_COLOR, MatchKind, Matcher,
+    SEARCH_MATCH_BG_COLOR,
+};
+use crate::log_message::LogReaderMessage;
+use crate::log_message::{
+    BookmarkMessage, ColumnMessage, ContextMenuMessage, DictionaryMessage, ExportMessage,
+    PresetMessage, ResultsMessage, RuleMessage, SelectionMessage, SeverityMessage, TimelineMessage,
+};
+use crate::presets_view;
+use crate::results_view::{self, Dock};
+use crate::search::SearchScope;
+use crate::severity::SeverityLevel;
+use crate::sort;
+use crate::timeline_view;
+use crate::virtual_list::virtual_list;
 
-use crate::LogReader;
-use crate::ViewDetail;
+use crate::scrollbar::scroll_bar;
+use crate::{LogReader, SCROLLBAR_WIDTH};
 
 const ROW_HIGHLIGHT_BG_COLOR_L: iced::Background =
     iced::Background::Color(iced::color!(188, 249, 84));
@@ -26,96 +43,271 @@ const ROW_HIGHLIGHT_BG_COLOR_D: iced::Background =
 const ROW_HIGHLIGHT_TXT_COLOR_L: iced::Color = iced::Color::BLACK;
 const ROW_HIGHLIGHT_TXT_COLOR_D: iced::Color = iced::Color::BLACK;
 
-const ROW_WARNING_BG_COLOR_L: iced::Background = iced::Background::Color(iced::color!(163, 140, 6));
-const ROW_WARNING_BG_COLOR_D: iced::Background =
-    iced::Background::Color(iced::color!(249, 225, 84));
+const RESIZE_HANDLE_WIDTH: f32 = 6.0;
+// Moving a header less than this is a click, which sorts
+const DRAG_THRESHOLD: f32 = 5.0;
 
-const ROW_WARNING_TXT_COLOR_L: iced::Color = iced::Color::WHITE;
-const ROW_WARNING_TXT_COLOR_D: iced::Color = iced::Color::BLACK;
+fn header_label(field: Field) -> &'static str {
+    match field {
+        Field::Id => "ID",
+        Field::Date => "Date",
+        Field::ComputerName => "Computer\nName",
+        Field::ProcessId => "Process ID",
+        Field::ProcessUser => "Process\nUser",
+        Field::ModuleName => "Module\nName",
+        Field::MessageId => "Message ID",
+        Field::LogLevel => "Log\nLevel",
+        Field::Facility => "Facility",
+        Field::LogMessage => "Log\nMessage",
+    }
+}
 
-fn check_str_filter(val: &MyFilter) -> Option<&str> {
-    if !val.value.is_empty() {
-        return Some(&val.value);
+/// The message sent when the filter input of the column changes.
+fn filter_message(field: Field) -> fn(String) -> LogReaderMessage {
+    match field {
+        Field::Id => LogReaderMessage::IDInput,
+        Field::Date => LogReaderMessage::DateInput,
+        Field::ComputerName => LogReaderMessage::ComputerNameInput,
+        Field::ProcessId => LogReaderMessage::ProcessIDInput,
+        Field::ProcessUser => LogReaderMessage::ProcessUserInput,
+        Field::ModuleName => LogReaderMessage::ModuleNameInput,
+        Field::MessageId => LogReaderMessage::MessageIDInput,
+        Field::LogLevel => LogReaderMessage::LogLevelInput,
+        Field::Facility => LogReaderMessage::FacilityInput,
+        Field::LogMessage => LogReaderMessage::LogMessageInput,
     }
-    None
 }
 
-fn check_num_filter<N>(val: &MyFilter) -> Option<N>
-where
-    N: Add<Output = N> + FromStr,
-{
-    if !val.value.is_empty() {
-        if let Ok(val) = val.value.parse::<N>() {
-            return Some(val);
-        }
+/// The filter mode of the column when it is not the usual one of its field.
+fn filter_placeholder(table: &LogReader, field: Field) -> &'static str {
+    let mode = table.filters.mode(field);
+    if mode == FilterMode::default_for(field) {
+        ""
+    } else {
+        mode.name()
     }
-    None
 }
 
 pub fn apply_filter(table: &mut LogReader) {
-    let mut filters: Vec<Box<dyn Fn(&Record) -> bool>> = vec![];
+    let conditions = table.filters.conditions();
+    let filters = filter::build(&table.filters, &conditions, &table.dictionary);
 
-    let t_filters = &table.filters;
+    // Narrow the records with the index first, the log message condition verifies the candidates
+    let log_message = &table.filters.log_message;
+    let candidates = (!log_message.is_empty()
+        && table.filters.mode(Field::LogMessage) != FilterMode::Excludes)
+        .then(|| table.search_index.as_ref()?.candidates(log_message))
+        .flatten();
+    let records: Box<dyn Iterator<Item = &Record>> = match candidates {
+        Some(candidates) => Box::new(candidates.into_iter().map(|idx| &table.events[idx])),
+        None => Box::new(table.events.iter()),
+    };
 
-    if !t_filters.date.value.is_empty() {
-        if let Ok(date) = table.filters.date.value.parse::<DateTime<Local>>() {
-            filters.push(Box::new(move |item: &Record| item.date.ge(&date)));
-        }
+    table.events_filtered = records
+        .filter(|&item| filter::matches(&filters, item))
+        .map(|r| r.id as usize)
+        .collect();
+    sort::sort_indexes(&table.sort_keys, &table.events, &mut table.events_filtered);
+    table.rows_generation += 1;
+
+    drop(filters);
+    table._clear_search_matches();
+    if table.results_panel {
+        table._refresh_search_results();
     }
+}
 
-    if let Some(txt) = check_str_filter(&t_filters.computer_name) {
-        filters.push(Box::new(move |item: &Record| {
-            item.computer_name.contains(&txt)
-        }));
+/// "Show only this" or "hide this" for the value shown in the cell of `field` of the record
+/// at `event_idx`.
+pub fn filter_by_value(table: &mut LogReader, event_idx: usize, field: Field, exclude: bool) {
+    let Some(record) = table.events.get(event_idx) else {
+        return;
+    };
+    let value = table.dictionary.display(field, record);
+
+    table.history.push(table._history_state());
+    if exclude {
+        table.filters.exclude_value(field, value);
+    } else {
+        table.filters.keep_value(field, value);
     }
 
-    if let Some(num) = check_num_filter(&t_filters.process_id) {
-        filters.push(Box::new(move |item: &Record| item.process_id == num));
+    apply_filter(table);
+
+    // Stay on the record, or on the one after it when it got hidden
+    let pos = match table
+        .events_filtered
+        .iter()
+        .position(|&idx| idx == event_idx)
+    {
+        Some(pos) => pos,
+        None if table.sort_keys.is_empty() => table
+            .events_filtered
+            .partition_point(|&idx| idx < event_idx),
+        None => table.highlighted_scroll_offset,
+    };
+    if !table.events_filtered.is_empty() {
+        table._scroll_to(pos.min(table.events_filtered.len() - 1) as u64);
     }
+}
+
+/// Sorts by the clicked column, Shift adds it as a further key.
+fn sort_by_column(table: &mut LogReader, field: Field) {
+    sort::toggle_sort(&mut table.sort_keys, field, table.modifiers.shift());
+    resort(table);
+}
 
-    if let Some(txt) = check_str_filter(&t_filters.process_user) {
-        filters.push(Box::new(move |item: &Record| {
-            item.process_user.contains(&txt)
-        }));
+/// Reorders the rows after the sort keys changed. The highlighted record stays highlighted.
+pub fn resort(table: &mut LogReader) {
+    let highlighted = table
+        .events_filtered
+        .get(table.highlighted_scroll_offset)
+        .copied();
+
+    sort::sort_indexes(&table.sort_keys, &table.events, &mut table.events_filtered);
+    table.rows_generation += 1;
+    table._clear_search_matches();
+    if table.results_panel {
+        table._refresh_search_results();
     }
 
-    if let Some(txt) = check_str_filter(&t_filters.module_name) {
-        filters.push(Box::new(move |item: &Record| {
-            item.module_name.contains(&txt)
-        }));
+    if let Some(pos) =
+        highlighted.and_then(|idx| table.events_filtered.iter().position(|&i| i == idx))
+    {
+        table._scroll_to(pos as u64);
     }
+}
+
+pub fn save_columns(table: &mut LogReader) {
+    let saved = table.columns.save(&table.log_format);
+    table._report_save("the column layout", saved);
+}
 
-    if let Some(num) = check_num_filter(&t_filters.message_id) {
-        filters.push(Box::new(move |item: &Record| item.message_id == num));
+fn column_update(table: &mut LogReader, msg: ColumnMessage) {
+    match msg {
+        ColumnMessage::HeaderPressed(field) => {
+            table.column_drag = Some(ColumnDrag::Move {
+                field,
+                start_x: table._mouse_x,
+                target: field,
+            });
+        }
+        ColumnMessage::HeaderEntered(field) => {
+            if let Some(ColumnDrag::Move { target, .. }) = table.column_drag.as_mut() {
+                *target = field;
+            }
+        }
+        ColumnMessage::ResizePressed(field) => {
+            if let Some(start_width) = table.columns.width(field) {
+                table.column_drag = Some(ColumnDrag::Resize {
+                    field,
+                    start_x: table._mouse_x,
+                    start_width,
+                });
+            }
+        }
+        ColumnMessage::CloseMenu => table.column_menu = false,
+        ColumnMessage::Visible(field, visible) => {
+            table.columns.set_visible(field, visible);
+            table.highlighted_column = table.columns.shown(table.highlighted_column);
+            save_columns(table);
+        }
+        ColumnMessage::Reset => {
+            table.columns = ColumnLayout::default();
+            save_columns(table);
+        }
     }
+}
+
+fn selection_update(table: &mut LogReader, msg: SelectionMessage) -> Task<LogReaderMessage> {
+    match msg {
+        SelectionMessage::RowClicked(event_idx) => {
+            let Some(pos) = table
+                .events_filtered
+                .iter()
+                .position(|&idx| idx == event_idx)
+            else {
+                return Task::none();
+            };
+            table.highlighted_scroll_offset = pos;
 
-    if let Some(num) = check_num_filter(&t_filters.log_level) {
-        filters.push(Box::new(move |item: &Record| item.log_level == num));
+            let id = event_idx as u64;
+            if table.modifiers.shift() {
+                table.selection.extend_to(&table.events_filtered, pos);
+            } else if table.modifiers.command() {
+                table.selection.toggle(id);
+            } else {
+                table.selection.select(id);
+            }
+        }
+        SelectionMessage::MarkAll => {
+            for id in table.selection.ids().collect::<Vec<_>>() {
+                table._add_mark(id);
+            }
+        }
+        SelectionMessage::Copy => return copy_rows(table),
+        SelectionMessage::Export => export_view::open(table, ExportScope::Selected),
+        SelectionMessage::FilterTo => {
+            table.history.push(table._history_state());
+            table.filters.included = table.selection.ids().collect();
+            apply_filter(table);
+            table._scroll_to(0);
+        }
+        SelectionMessage::Clear => table.selection.clear(),
     }
+    Task::none()
+}
 
-    if let Some(num) = check_num_filter(&t_filters.facility) {
-        filters.push(Box::new(move |item: &Record| item.facility == num));
+/// Ctrl+C: the selected rows, or the highlighted one when nothing is selected.
+pub fn copy_rows(table: &LogReader) -> Task<LogReaderMessage> {
+    let records = if table.selection.is_empty() {
+        table
+            .events_filtered
+            .get(table.highlighted_scroll_offset)
+            .map(|&event_idx| &table.events[event_idx])
+            .into_iter()
+            .collect()
+    } else {
+        table._selected_records()
+    };
+    if records.is_empty() {
+        return Task::none();
     }
 
-    if let Some(txt) = check_str_filter(&t_filters.log_message) {
-        filters.push(Box::new(move |item: &Record| {
-            item.log_message.contains(&txt)
-        }));
+    let (fields, rows) = table._visible_rows(&records);
+    iced::clipboard::write(copy::format_rows(table.copy_format, &fields, &rows))
+}
+
+/// Ctrl+Shift+C: the value of the highlighted cell.
+fn copy_cell(table: &LogReader) -> Task<LogReaderMessage> {
+    match table.events_filtered.get(table.highlighted_scroll_offset) {
+        Some(&event_idx) => iced::clipboard::write(
+            table
+                .dictionary
+                .display(table.highlighted_column, &table.events[event_idx]),
+        ),
+        None => Task::none(),
     }
+}
 
-    table.events_filtered = table
-        .events
-        .iter()
-        .filter(|&item| {
-            for f in filters.iter() {
-                if !f(item) {
-                    return false;
-                }
+/// Ends a header drag on mouse release, a header released in place was clicked.
+fn finish_column_drag(table: &mut LogReader) {
+    match table.column_drag.take() {
+        Some(ColumnDrag::Resize { .. }) => save_columns(table),
+        Some(ColumnDrag::Move {
+            field,
+            start_x,
+            target,
+        }) => {
+            if (table._mouse_x - start_x).abs() < DRAG_THRESHOLD {
+                sort_by_column(table, field);
+            } else if target != field {
+                table.columns.move_column(field, target);
+                save_columns(table);
             }
-            return true;
-        })
-        .map(|r| r.id as usize)
-        .collect();
+        }
+        None => (),
+    }
 }
 
 pub fn update(table: &mut LogReader, msg: LogReaderMessage) -> Task<LogReaderMessage> {
@@ -126,10 +318,7 @@ pub fn update(table: &mut LogReader, msg: LogReaderMessage) -> Task<LogReaderMes
                 // row.name = "Something".to_owned();
             }
         } */
-        LogReaderMessage::ScrollChanged(value) => {
-            let target_change = table.events_filtered.len() as f64 - (value - 1.0);
-            table._scroll_highlight(table.scroll_value - target_change);
-        }
+        LogReaderMessage::ScrollChanged(value) => table._set_scroll(value),
 
         LogReaderMessage::TableEntered => {
             table._mouse_on_table = true;
@@ -140,9 +329,57 @@ pub fn update(table: &mut LogReader, msg: LogReaderMessage) -> Task<LogReaderMes
         LogReaderMessage::Event(event) => match event {
             IcedEvent::Keyboard(keyboard_event) => match keyboard_event {
                 keyboard::Event::KeyPressed { key, modifiers, .. } => match key {
+                    iced::keyboard::Key::Named(keyboard::key::Named::ArrowLeft)
+                        if modifiers.alt() =>
+                    {
+                        table._history_back();
+                    }
+                    iced::keyboard::Key::Named(keyboard::key::Named::ArrowRight)
+                        if modifiers.alt() =>
+                    {
+                        table._history_forward();
+                    }
+                    iced::keyboard::Key::Character(c)
+                        if c == "z" && modifiers.command() && modifiers.shift() =>
+                    {
+                        table._redo_filter();
+                    }
+                    iced::keyboard::Key::Character(c) if c == "z" && modifiers.command() => {
+                        table._undo_filter();
+                    }
+                    iced::keyboard::Key::Character(c)
+                        if c == "c" && modifiers.command() && !table.searching_bar =>
+                    {
+                        return if modifiers.shift() {
+                            copy_cell(table)
+                        } else {
+                            copy_rows(table)
+                        };
+                    }
+                    iced::keyboard::Key::Character(c) if c == "y" && modifiers.command() => {
+                        table._redo_filter();
+                    }
+                    iced::keyboard::Key::Named(keyboard::key::Named::ArrowDown)
+                        if modifiers.shift() =>
+                    {
+                        table._extend_selection(-1.0)
+                    }
+                    iced::keyboard::Key::Named(keyboard::key::Named::ArrowUp)
+                        if modifiers.shift() =>
+                    {
+                        table._extend_selection(1.0)
+                    }
                     iced::keyboard::Key::Named(keyboard::key::Named::ArrowDown) => {
                         table._scroll_highlight(-1.0)
                     }
+                    iced::keyboard::Key::Named(keyboard::key::Named::ArrowLeft) => {
+                        table.highlighted_column =
+                            table.columns.step(table.highlighted_column, false);
+                    }
+                    iced::keyboard::Key::Named(keyboard::key::Named::ArrowRight) => {
+                        table.highlighted_column =
+                            table.columns.step(table.highlighted_column, true);
+                    }
                     iced::keyboard::Key::Named(keyboard::key::Named::ArrowUp) => {
                         table._scroll_highlight(1.0)
                     }
@@ -159,22 +396,13 @@ pub fn update(table: &mut LogReader, msg: LogReaderMessage) -> Task<LogReaderMes
                         table._scroll_highlight(table.rows_visible as f64)
                     }
                     iced::keyboard::Key::Named(keyboard::key::Named::Enter)
-                        if table.searching_bar == false =>
+                        if !table.searching_bar =>
                     {
-                        let selected_row = table
-                            .events
-                            .get(
-                                *table
-                                    .events_filtered
-                                    .get(table.highlighted_scroll_offset)
-                                    .unwrap(),
-                            )
-                            .unwrap();
-
-                        table.view_detail = Some(ViewDetail {
-                            record: selected_row.clone(),
-                            content: text_editor::Content::with_text(&selected_row.log_message),
-                        });
+                        if let Some(&event_idx) =
+                            table.events_filtered.get(table.highlighted_scroll_offset)
+                        {
+                            table._open_detail(event_idx);
+                        }
                     }
                     iced::keyboard::Key::Named(keyboard::key::Named::F3) if modifiers.shift() => {
                         table._find_prev();
@@ -182,37 +410,70 @@ pub fn update(table: &mut LogReader, msg: LogReaderMessage) -> Task<LogReaderMes
                     iced::keyboard::Key::Named(keyboard::key::Named::F3) => {
                         table._find_next();
                     }
+                    iced::keyboard::Key::Named(keyboard::key::Named::F2) => {
+                        bookmarks_view::update(table, BookmarkMessage::TogglePanel);
+                    }
+                    iced::keyboard::Key::Named(keyboard::key::Named::F4) => {
+                        results_view::update(table, ResultsMessage::Toggle);
+                    }
                     iced::keyboard::Key::Named(keyboard::key::Named::Escape)
-                        if table.searching_bar == true =>
+                        if table.searching_bar =>
                     {
                         table.searching_bar = false;
                     }
+                    iced::keyboard::Key::Named(keyboard::key::Named::Escape)
+                        if table.context_menu.is_some() =>
+                    {
+                        table.context_menu = None;
+                    }
+                    iced::keyboard::Key::Named(keyboard::key::Named::Escape)
+                        if table.column_menu =>
+                    {
+                        table.column_menu = false;
+                    }
+                    iced::keyboard::Key::Named(keyboard::key::Named::Escape)
+                        if !table.selection.is_empty() =>
+                    {
+                        return selection_update(table, SelectionMessage::Clear);
+                    }
                     iced::keyboard::Key::Named(keyboard::key::Named::Enter)
-                        if table.searching_bar == true =>
+                        if table.searching_bar =>
                     {
                         table.searching_bar = false;
                         table._find_next();
                     }
-                    iced::keyboard::Key::Character(c)
-                        if c == "/" && table.searching_bar == false =>
-                    {
+                    iced::keyboard::Key::Character(c) if c == "/" && !table.searching_bar => {
                         table.searching_bar = true;
                         return focus(table.searching_bar_id.clone());
                     }
                     iced::keyboard::Key::Character(c) if c == "n" || c == "N" => {
-                        if table.selected_rows.len() == 0 {
-                            return Task::none();
+                        if let Some((pos, _row_idx)) = table._next_mark(!modifiers.shift()) {
+                            table._jump_to(pos as u64);
                         }
-                        let mark_pos;
-                        if modifiers.shift() {
-                            mark_pos = table._get_next_mark_rev();
-                        } else {
-                            mark_pos = table._get_next_mark();
+                    }
+                    iced::keyboard::Key::Character(c) if c == "h" && modifiers.command() => {
+                        return Task::done(LogReaderMessage::Rule(RuleMessage::OpenDialog));
+                    }
+                    iced::keyboard::Key::Character(c) if c == "p" && modifiers.command() => {
+                        return Task::done(LogReaderMessage::Preset(PresetMessage::OpenDialog));
+                    }
+                    iced::keyboard::Key::Character(c) if c == "f" || c == "x" => {
+                        if let Some(&event_idx) =
+                            table.events_filtered.get(table.highlighted_scroll_offset)
+                        {
+                            filter_by_value(table, event_idx, table.highlighted_column, c == "x");
                         }
-                        if let Some((scroll_idx, _row_idx)) = mark_pos {
-                            table._scroll_to(scroll_idx);
+                    }
+                    iced::keyboard::Key::Character(c) if c == "e" => {
+                        if let Some(&event_idx) =
+                            table.events_filtered.get(table.highlighted_scroll_offset)
+                        {
+                            table._toggle_expanded(event_idx);
                         }
                     }
+                    iced::keyboard::Key::Character(c) if c == "E" => {
+                        table.expanded_rows.clear();
+                    }
                     iced::keyboard::Key::Character(c) if c == "m" => {
                         table._switch_mark_highlighted_offset();
                     }
@@ -236,87 +497,145 @@ pub fn update(table: &mut LogReader, msg: LogReaderMessage) -> Task<LogReaderMes
                     table._scroll(y as f64);
                     // scroll here
                 }
+                // Touchpads scroll by pixels, which moves the view smoothly without the highlight
+                mouse::Event::WheelScrolled {
+                    delta: mouse::ScrollDelta::Pixels { y, .. },
+                } => {
+                    table._scroll((y / table.row_height) as f64);
+                }
                 mouse::Event::CursorMoved { position } => {
-                    table._mouse_y = position.y - table.header_height;
-                    if table._mouse_y < 0.0 {
+                    table._mouse_x = position.x;
+                    if let Some(ColumnDrag::Resize {
+                        field,
+                        start_x,
+                        start_width,
+                    }) = table.column_drag
+                    {
+                        table
+                            .columns
+                            .resize(field, start_width + position.x - start_x);
+                        return Task::none();
+                    }
+
+                    table._mouse_y = position.y - (table._table_top() + table.header_height);
+                    if table._mouse_y < 0.0
+                        || !table._mouse_on_table
+                        || table.context_menu.is_some()
+                    {
                         return Task::none();
                     }
 
                     // table.highlighted = (pos_y / (table.rows_visible as f32 - (30.0 / table.row_height).ceil())) as u64;
                     /* table.highlighted_row_id = table._scroll_value_to_row_id(((table._mouse_y / table.row_height as f32) as f64
                     + (table.scroll_value)) as u64); */
-                    table.highlighted_scroll_offset =
-                        ((table._mouse_y / table.row_height as f32) as f64 + (table.scroll_value))
-                            as usize;
-                }
-                /* mouse::Event::ButtonReleased(btn) => {
-                    if table._mouse_y > 0.0 && table._mouse_on_table && btn == mouse::Button::Left {
-                        table._switch_mark(
-                            *table
-                                .events_filtered
-                                .get(table.highlighted_row_offset)
-                                .unwrap() as u64,
-                        );
-                    }
-                } */
-                _ => (),
-            },
-            IcedEvent::Window(window_event) => match window_event {
-                window::Event::Resized(iced::Size { height, .. }) => {
-                    table.window_height = height;
-                    table.rows_visible =
-                        ((height - (table.header_height + 30.0)) / table.row_height) as u32
+                    table.highlighted_scroll_offset = table._row_at(table._mouse_y);
                 }
+                mouse::Event::ButtonReleased(mouse::Button::Left) => finish_column_drag(table),
                 _ => (),
             },
             _ => (),
         },
 
         LogReaderMessage::IDInput(txt) => {
-            table.filters.log_name.value = txt;
+            table._record_filter_edit("log_name");
+            table.filters.log_name = txt;
             apply_filter(table);
             // apply_filter(table, |&r| r.log_name.contains(&txt));
         }
         LogReaderMessage::DateInput(txt) => {
-            table.filters.date.value = txt;
+            table._record_filter_edit("date");
+            table.filters.date = txt;
             apply_filter(table);
         }
         LogReaderMessage::ComputerNameInput(txt) => {
-            table.filters.computer_name.value = txt;
+            table._record_filter_edit("computer_name");
+            table.filters.computer_name = txt;
             apply_filter(table);
         }
         LogReaderMessage::ProcessIDInput(txt) => {
-            table.filters.process_id.value = txt;
+            table._record_filter_edit("process_id");
+            table.filters.process_id = txt;
             apply_filter(table);
         }
         LogReaderMessage::ProcessUserInput(txt) => {
-            table.filters.process_user.value = txt;
+            table._record_filter_edit("process_user");
+            table.filters.process_user = txt;
             apply_filter(table);
         }
         LogReaderMessage::ModuleNameInput(txt) => {
-            table.filters.module_name.value = txt;
+            table._record_filter_edit("module_name");
+            table.filters.module_name = txt;
             apply_filter(table);
         }
         LogReaderMessage::MessageIDInput(txt) => {
-            table.filters.message_id.value = txt;
+            table._record_filter_edit("message_id");
+            table.filters.message_id = txt;
             apply_filter(table);
         }
         LogReaderMessage::LogLevelInput(txt) => {
-            table.filters.log_level.value = txt;
+            table._record_filter_edit("log_level");
+            table.filters.log_level = txt;
             apply_filter(table);
         }
         LogReaderMessage::FacilityInput(txt) => {
-            table.filters.facility.value = txt;
+            table._record_filter_edit("facility");
+            table.filters.facility = txt;
             apply_filter(table);
         }
         LogReaderMessage::LogMessageInput(txt) => {
-            table.filters.log_message.value = txt;
+            table._record_filter_edit("log_message");
+            table.filters.log_message = txt;
+            apply_filter(table);
+        }
+        LogReaderMessage::CellHovered(field) => {
+            table.highlighted_column = field;
+        }
+        LogReaderMessage::Column(msg) => column_update(table, msg),
+        LogReaderMessage::RowDoubleClicked(event_idx) => table._open_detail(event_idx),
+        LogReaderMessage::Selection(msg) => return selection_update(table, msg),
+        LogReaderMessage::Bookmark(msg) => bookmarks_view::update(table, msg),
+        LogReaderMessage::ContextMenu(msg) => return context_menu::update(table, msg),
+        LogReaderMessage::RemoveExclusion(idx) if idx < table.filters.excluded.len() => {
+            table.history.push(table._history_state());
+            table.filters.excluded.remove(idx);
+            apply_filter(table);
+        }
+        LogReaderMessage::RemoveKept(idx) if idx < table.filters.kept.len() => {
+            table.history.push(table._history_state());
+            table.filters.kept.remove(idx);
             apply_filter(table);
         }
+        LogReaderMessage::CopyFormatSelected(format) => table.copy_format = format,
+        LogReaderMessage::RemoveInclusion => {
+            table.history.push(table._history_state());
+            table.filters.included.clear();
+            apply_filter(table);
+        }
+        LogReaderMessage::Results(results_msg) => results_view::update(table, results_msg),
+        LogReaderMessage::Timeline(timeline_msg) => timeline_view::update(table, timeline_msg),
+        LogReaderMessage::DismissStatus => table.status_error = None,
+        LogReaderMessage::HistoryBack => table._history_back(),
+        LogReaderMessage::HistoryForward => table._history_forward(),
         LogReaderMessage::SearchInputChanged(txt) => {
             table.searching_text = txt;
+            table._reset_search();
+        }
+        LogReaderMessage::SearchRegexToggled(value) => {
+            table.search_options.regex = value;
+            table._reset_search();
+        }
+        LogReaderMessage::SearchCaseInsensitiveToggled(value) => {
+            table.search_options.case_insensitive = value;
+            table._reset_search();
+        }
+        LogReaderMessage::SearchWholeWordToggled(value) => {
+            table.search_options.whole_word = value;
+            table._reset_search();
+        }
+        LogReaderMessage::SearchScopeSelected(scope) => {
+            table.search_options.scope = scope;
+            table._reset_search();
         }
-        LogReaderMessage::WindowClosed(_window_id) => {}
         _ => (),
     }
 
@@ -324,26 +643,224 @@ pub fn update(table: &mut LogReader, msg: LogReaderMessage) -> Task<LogReaderMes
 }
 
 pub fn view(table: &LogReader) -> Element<'_, LogReaderMessage> {
-    let container_table_slider = _build_table(&table);
+    let container_table_slider = _build_table(table);
 
-    let mut comp_stack = stack!(container_table_slider.width(Fill).height(Fill),);
+    let mut content = Row::new();
+    if table.results_panel && table.results_dock == Dock::Left {
+        content = content.push(results_view::view(table));
+    }
+    content = content.push(container_table_slider.width(Fill).height(Fill));
+    if table.results_panel && table.results_dock == Dock::Right {
+        content = content.push(results_view::view(table));
+    }
+    if table.bookmarks_panel {
+        content = content.push(bookmarks_view::view(table));
+    }
+
+    let mut comp_stack = stack!(column![
+        _build_toolbar(table),
+        table.timeline.then(|| timeline_view::view(table)),
+        content
+    ]);
 
     if table.searching_bar {
-        comp_stack = comp_stack.push(_build_search_window(&table));
+        comp_stack = comp_stack.push(_build_search_window(table));
+    }
+
+    if table.column_menu {
+        comp_stack = comp_stack.push(_build_column_menu(table));
+    }
+
+    if let Some(menu) = &table.context_menu {
+        comp_stack = comp_stack.push(context_menu::view(table, menu));
     }
 
     comp_stack.into()
 }
 
+fn _build_toolbar(log_table: &LogReader) -> Container<'_, LogReaderMessage> {
+    container(
+        row![
+            button("Back").on_press_maybe(
+                log_table
+                    .history
+                    .can_go_back()
+                    .then_some(LogReaderMessage::HistoryBack)
+            ),
+            button("Forward").on_press_maybe(
+                log_table
+                    .history
+                    .can_go_forward()
+                    .then_some(LogReaderMessage::HistoryForward)
+            ),
+            presets_view::picker(log_table),
+            pick_list(
+                CopyFormat::ALL,
+                Some(log_table.copy_format),
+                LogReaderMessage::CopyFormatSelected
+            )
+            .placeholder("Copy as"),
+            button("Results").on_press(LogReaderMessage::Results(ResultsMessage::Toggle)),
+            button("Timeline").on_press(LogReaderMessage::Timeline(TimelineMessage::Toggle)),
+            button("Export").on_press(LogReaderMessage::Export(ExportMessage::OpenDialog(
+                ExportScope::Shown
+            ))),
+            button("Bookmarks").on_press(LogReaderMessage::Bookmark(BookmarkMessage::TogglePanel)),
+            button("Rules").on_press(LogReaderMessage::Rule(RuleMessage::OpenDialog)),
+            button("Severity").on_press(LogReaderMessage::Severity(SeverityMessage::OpenDialog)),
+            button("Dictionary")
+                .on_press(LogReaderMessage::Dictionary(DictionaryMessage::OpenDialog)),
+            Row::with_children(log_table.filters.kept.iter().enumerate().map(
+                |(idx, (field, value))| {
+                    button(text(format!("{} = {}  \u{2715}", field, value)))
+                        .style(button::secondary)
+                        .on_press(LogReaderMessage::RemoveKept(idx))
+                        .into()
+                }
+            ))
+            .spacing(4),
+            Row::with_children(log_table.filters.excluded.iter().enumerate().map(
+                |(idx, (field, value))| {
+                    button(text(format!("{} \u{2260} {}  \u{2715}", field, value)))
+                        .style(button::secondary)
+                        .on_press(LogReaderMessage::RemoveExclusion(idx))
+                        .into()
+                }
+            ))
+            .spacing(4),
+            (!log_table.filters.included.is_empty()).then(|| {
+                button(text(format!(
+                    "{} selected rows  \u{2715}",
+                    log_table.filters.included.len()
+                )))
+                .style(button::secondary)
+                .on_press(LogReaderMessage::RemoveInclusion)
+            }),
+            (!log_table.filters.date_before.is_empty()).then(|| {
+                button(text(format!(
+                    "Before {}  \u{2715}",
+                    log_table.filters.date_before
+                )))
+                .style(button::secondary)
+                .on_press(LogReaderMessage::Timeline(
+                    TimelineMessage::RemoveDateBefore,
+                ))
+            }),
+            _build_selection_bar(log_table),
+            iced::widget::space().width(Fill),
+            log_table.status_error.as_ref().map(|error| {
+                button(text(format!("{}  ✕", error)))
+                    .style(button::danger)
+                    .on_press(LogReaderMessage::DismissStatus)
+            }),
+            text(&log_table.search_status),
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center),
+    )
+    .padding([0, 8])
+    .center_y(log_table.toolbar_height)
+}
+
+fn _build_selection_bar(log_table: &LogReader) -> Option<Row<'_, LogReaderMessage>> {
+    if log_table.selection.is_empty() {
+        return None;
+    }
+    let msg = LogReaderMessage::Selection;
+
+    Some(
+        row![
+            text(format!("{} selected", log_table.selection.len())),
+            button("Mark").on_press(msg(SelectionMessage::MarkAll)),
+            button("Copy").on_press(msg(SelectionMessage::Copy)),
+            button("Export").on_press(msg(SelectionMessage::Export)),
+            button("Filter").on_press(msg(SelectionMessage::FilterTo)),
+            button("Clear")
+                .style(button::secondary)
+                .on_press(msg(SelectionMessage::Clear)),
+        ]
+        .spacing(4)
+        .align_y(iced::Alignment::Center),
+    )
+}
+
+fn _build_column_menu(log_table: &LogReader) -> Container<'_, LogReaderMessage> {
+    let msg = LogReaderMessage::Column;
+
+    let columns = log_table.columns.columns.iter().map(|column| {
+        let field = column.field;
+        checkbox(column.visible)
+            .label(field.name())
+            .on_toggle(move |visible| msg(ColumnMessage::Visible(field, visible)))
+            .into()
+    });
+
+    container(
+        container(
+            column![
+                text("Columns"),
+                Column::with_children(columns).spacing(4),
+                row![
+                    button("Reset Layout").on_press(msg(ColumnMessage::Reset)),
+                    button("Close").on_press(msg(ColumnMessage::CloseMenu)),
+                ]
+                .spacing(8),
+            ]
+            .spacing(8)
+            .padding(12),
+        )
+        .style(|theme: &Theme| {
+            container::Style::default()
+                .background(theme.palette().background)
+                .border(
+                    iced::Border::default()
+                        .width(2)
+                        .color(theme.palette().success),
+                )
+        }),
+    )
+    .padding(iced::Padding::default().top(log_table._table_top() + log_table.header_height))
+}
+
 fn _build_search_window(log_table: &LogReader) -> Container<'_, LogReaderMessage> {
+    let options = &log_table.search_options;
+
+    let status = match &log_table.search_error {
+        Some(error) => text(error).style(text::danger),
+        None => text(&log_table.search_status),
+    };
+
     container(
         container(
             column![
-                text("Find in Log Message"),
+                text("Find"),
                 text_input("", &log_table.searching_text)
                     .on_input(LogReaderMessage::SearchInputChanged)
                     .id(log_table.searching_bar_id.clone())
                     .width(500),
+                row![
+                    checkbox(options.regex)
+                        .label("Regex")
+                        .on_toggle(LogReaderMessage::SearchRegexToggled),
+                    checkbox(options.case_insensitive)
+                        .label("Case insensitive")
+                        .on_toggle(LogReaderMessage::SearchCaseInsensitiveToggled),
+                    checkbox(options.whole_word)
+                        .label("Whole word")
+                        .on_toggle(LogReaderMessage::SearchWholeWordToggled),
+                ]
+                .spacing(16),
+                row![
+                    text("Search in"),
+                    pick_list(
+                        SearchScope::options(),
+                        Some(options.scope),
+                        LogReaderMessage::SearchScopeSelected,
+                    ),
+                ]
+                .spacing(8)
+                .align_y(iced::Alignment::Center),
+                status,
             ]
             .padding(16)
             .spacing(16),
@@ -361,77 +878,219 @@ fn _build_search_window(log_table: &LogReader) -> Container<'_, LogReaderMessage
     .center(Fill)
 }
 
-fn _build_table(log_table: &LogReader) -> Row<'_, LogReaderMessage> {
-    let header_impl = move |header, filter_ref: &MyFilter| {
-        let filter_input = text_input("", &filter_ref.value).on_input(filter_ref.message);
-
-        container(column![
-            text(header)
-                .font(Font {
-                    weight: font::Weight::Bold,
-                    ..Font::DEFAULT
-                })
-                .wrapping(text::Wrapping::None)
-                .height(Fill),
-            filter_input,
-        ])
-        .clip(true)
-        .height(log_table.header_height)
+/// Cell text with the search and filter matches rendered on a highlight background.
+fn highlighted_text<'a>(
+    matcher: &Matcher,
+    field: Field,
+    value: String,
+    expanded: bool,
+) -> Element<'a, LogReaderMessage> {
+    let wrapping = match field {
+        Field::LogMessage if expanded => text::Wrapping::WordOrGlyph,
+        Field::Date | Field::LogMessage => text::Wrapping::None,
+        _ => text::Wrapping::default(),
     };
 
-    let body_modifier = |record: &Record, input_some| {
-        let Record { id, facility, .. } = *record;
+    let ranges = matcher.ranges(field, &value);
+    if ranges.is_empty() {
+        return text(value).wrapping(wrapping).into();
+    }
 
-        container(input_some)
-            .clip(true)
+    let mut spans = vec![];
+    let mut last = 0;
+    for (range, kind) in ranges {
+        if last < range.start {
+            spans.push(span(value[last..range.start].to_owned()));
+        }
+        let bg_color = match kind {
+            MatchKind::Search => SEARCH_MATCH_BG_COLOR,
+            MatchKind::Group => GROUP_MATCH_BG_COLOR,
+            MatchKind::Filter => FILTER_MATCH_BG_COLOR,
+        };
+        spans.push(
+            span(value[range.clone()].to_owned())
+                .background(bg_color)
+                .color(MATCH_TXT_COLOR),
+        );
+        last = range.end;
+    }
+    if last < value.len() {
+        spans.push(span(value[last..].to_owned()));
+    }
+
+    rich_text(spans)
+        .wrapping(wrapping)
+        .on_link_click(never)
+        .into()
+}
+
+/// Colors of a row from the severity scheme, the highlight rules and its mark.
+#[derive(Clone, Copy)]
+struct RowColors<'a> {
+    severity: Option<&'a SeverityLevel>,
+    rule: (Option<iced::Color>, Option<iced::Color>),
+    mark: Option<iced::Color>,
+}
+
+fn _build_table<'a>(log_table: &'a LogReader) -> Row<'a, LogReaderMessage> {
+    let header_impl = move |field: Field| {
+        let msg = LogReaderMessage::Column;
+        let filter_input = text_input(
+            filter_placeholder(log_table, field),
+            log_table.filters.get(field),
+        )
+        .on_input(filter_message(field));
+
+        // Arrow of the sort direction, numbered when sorting by more than one column
+        let keys = &log_table.sort_keys;
+        let sort_indicator = match keys.iter().position(|key| key.field == field) {
+            Some(idx) => format!(
+                " {}{}",
+                if keys[idx].descending {
+                    "\u{25bc}"
+                } else {
+                    "\u{25b2}"
+                },
+                if keys.len() > 1 {
+                    (idx + 1).to_string()
+                } else {
+                    String::default()
+                }
+            ),
+            None => String::default(),
+        };
+
+        let is_drop_target = matches!(
+            log_table.column_drag,
+            Some(ColumnDrag::Move { field: dragged, target, .. }) if target == field && dragged != field
+        );
+
+        let title = mouse_area(
+            container(
+                text(format!("{}{}", header_label(field), sort_indicator))
+                    .font(Font {
+                        weight: font::Weight::Bold,
+                        ..Font::DEFAULT
+                    })
+                    .wrapping(text::Wrapping::None),
+            )
             .width(Fill)
-            .height(log_table.row_height)
+            .height(Fill)
+            .style(move |theme: &Theme| {
+                if is_drop_target {
+                    container::Style::default()
+                        .background(theme.extended_palette().primary.weak.color)
+                } else {
+                    container::Style::default()
+                }
+            }),
+        )
+        .on_press(msg(ColumnMessage::HeaderPressed(field)))
+        .on_right_press(LogReaderMessage::ContextMenu(ContextMenuMessage::Open(
+            MenuTarget::Header(field),
+        )))
+        .on_enter(msg(ColumnMessage::HeaderEntered(field)))
+        .interaction(mouse::Interaction::Pointer);
+
+        let mut header = Row::new().push(column![title, filter_input].width(Fill));
+        // The column filling the remaining space has no width to drag
+        if log_table.columns.width(field).is_some() {
+            header = header.push(
+                mouse_area(
+                    container(iced::widget::rule::vertical(1))
+                        .width(RESIZE_HANDLE_WIDTH)
+                        .height(Fill)
+                        .align_right(RESIZE_HANDLE_WIDTH),
+                )
+                .on_press(msg(ColumnMessage::ResizePressed(field)))
+                .interaction(mouse::Interaction::ResizingHorizontally),
+            );
+        }
+
+        container(header).clip(true).height(log_table.header_height)
+    };
+
+    let matcher = Matcher::new(log_table.search_query.clone(), log_table.filters.terms());
+
+    let message_width = log_table._message_column_width();
+
+    let body_modifier = move |record: &Record, colors: RowColors<'a>, field: Field| {
+        let Record { id, .. } = *record;
+        let RowColors {
+            severity,
+            rule: (rule_bg_color, rule_txt_color),
+            mark: mark_color,
+        } = colors;
+        let expanded = log_table.expanded_rows.contains(&id);
+        let selected = log_table.selection.contains(id);
+
+        let value = log_table.dictionary.display(field, record);
+        // An expanded message wraps at the visible width instead of scrolling sideways
+        let width = match field {
+            Field::LogMessage if expanded => iced::Length::Fixed(message_width),
+            _ => Fill,
+        };
+        let cell = container(highlighted_text(&matcher, field, value, expanded))
+            .clip(true)
+            .width(width)
+            .height(log_table._row_height(id as usize))
             .padding(log_table.row_padding)
             .style(move |theme: &Theme| {
                 let mut bg_color: Option<iced::Background> = None;
                 let mut txt_color: Option<iced::Color> = None;
 
                 let is_dark = theme.extended_palette().is_dark;
+                let mut border = iced::Border::default();
 
-                if facility == 3 {
-                    //warning
-                    if is_dark {
-                        bg_color = Some(ROW_WARNING_BG_COLOR_D);
-                        txt_color = Some(ROW_WARNING_TXT_COLOR_D);
-                    } else {
-                        bg_color = Some(ROW_WARNING_BG_COLOR_L);
-                        txt_color = Some(ROW_WARNING_TXT_COLOR_L);
-                    }
-                } else if facility == 4 {
-                    //error
-                    bg_color = Some(iced::Background::Color(iced::color!(240, 44, 44)));
-                    txt_color = Some(iced::Color::BLACK);
+                if let Some(level) = severity {
+                    let (level_bg_color, level_txt_color) = level.colors(is_dark);
+                    bg_color = level_bg_color.map(iced::Background::Color);
+                    txt_color = level_txt_color;
+                }
+
+                // Precedence from lowest: severity, highlight rules, marks, selection,
+                // highlighted row
+                if let Some(color) = rule_bg_color {
+                    bg_color = Some(iced::Background::Color(color));
+                }
+                if let Some(color) = rule_txt_color {
+                    txt_color = Some(color);
                 }
 
-                if log_table.selected_rows.contains(&id) {
-                    bg_color = Some(iced::Background::Color(iced::color!(0, 255, 0)));
+                if let Some(color) = mark_color {
+                    bg_color = Some(iced::Background::Color(color));
                     txt_color = Some(iced::color!(0, 0, 0));
                     // txt_color = Some(theme.palette().background);
                 }
 
+                if selected {
+                    let pair = theme.extended_palette().primary.weak;
+                    bg_color = Some(iced::Background::Color(pair.color));
+                    txt_color = Some(pair.text);
+                }
+
                 if let Some(sel_id) = log_table
                     .events_filtered
                     .get(log_table.highlighted_scroll_offset)
+                    && id as usize == *sel_id
                 {
-                    if id as usize == *sel_id {
-                        if is_dark {
-                            bg_color = Some(ROW_HIGHLIGHT_BG_COLOR_D);
-                            txt_color = Some(ROW_HIGHLIGHT_TXT_COLOR_D)
-                        } else {
-                            bg_color = Some(ROW_HIGHLIGHT_BG_COLOR_L);
-                            txt_color = Some(ROW_HIGHLIGHT_TXT_COLOR_L);
-                        }
+                    if is_dark {
+                        bg_color = Some(ROW_HIGHLIGHT_BG_COLOR_D);
+                        txt_color = Some(ROW_HIGHLIGHT_TXT_COLOR_D)
+                    } else {
+                        bg_color = Some(ROW_HIGHLIGHT_BG_COLOR_L);
+                        txt_color = Some(ROW_HIGHLIGHT_TXT_COLOR_L);
+                    }
+
+                    if field == log_table.highlighted_column {
+                        border = border.width(2).color(iced::Color::BLACK);
                     }
                 }
 
                 container::Style {
                     background: bg_color,
                     text_color: txt_color,
+                    border,
                     shadow: iced::Shadow {
                         color: color!(0),
                         offset: Vector { x: 0.0, y: 0.0 },
@@ -439,121 +1098,92 @@ fn _build_table(log_table: &LogReader) -> Row<'_, LogReaderMessage> {
                     },
                     ..container::Style::default()
                 }
-            })
-    };
+            });
+
+        let cell: Element<'_, LogReaderMessage> = match log_table.dictionary.describe(field, record)
+        {
+            Some(description) => tooltip(
+                cell,
+                container(text(description))
+                    .padding(6)
+                    .style(container::rounded_box),
+                tooltip::Position::Bottom,
+            )
+            .into(),
+            None => cell.into(),
+        };
 
-    let items = log_table.events_filtered.iter();
+        mouse_area(cell)
+            .on_press(LogReaderMessage::Selection(SelectionMessage::RowClicked(
+                id as usize,
+            )))
+            .on_double_click(LogReaderMessage::RowDoubleClicked(id as usize))
+            .on_enter(LogReaderMessage::CellHovered(field))
+            .on_right_press(LogReaderMessage::ContextMenu(ContextMenuMessage::Open(
+                MenuTarget::Row(id as usize, field),
+            )))
+    };
 
-    let scroller_value = items.len() as f64 - log_table.scroll_value;
-    let slider = vertical_slider(
-        // (log_table.rows_visible as f64)..=((items.len() + 1) as f64),
-        (log_table.rows_visible as f64)..=((items.len() + 1) as f64),
-        scroller_value,
+    let scrollbar = scroll_bar(
+        log_table.events_filtered.len() as f64,
+        log_table.rows_visible as f64,
+        log_table._max_scroll(),
+        log_table.scroll_value,
+        &log_table.minimap,
         LogReaderMessage::ScrollChanged,
     )
-    .style(|theme, status| vertical_slider::Style {
-        rail: slider::Rail {
-            backgrounds: (
-                iced::Background::Color(iced::color!(128, 128, 128)),
-                iced::Background::Color(iced::color!(128, 128, 128)),
-            ),
-            width: 2.0,
-            border: iced::Border::default(),
-        },
-        ..vertical_slider::default(theme, status)
-    });
-
-    let columns_def = [
-        LogColumn::new(
-            header_impl("ID", &log_table.filters.log_name),
-            |r: &Record| body_modifier(r, text(&r.id)),
-            100,
-        ),
-        LogColumn::new(
-            header_impl("Date", &log_table.filters.date),
-            |r: &Record| {
-                body_modifier(
-                    r,
-                    text(r.date.format("%d. %m. %Y %H:%M:%S%.3f").to_string())
-                        .wrapping(text::Wrapping::None),
-                )
-            },
-            200,
-        ),
-        LogColumn::new(
-            header_impl("Computer\nName", &log_table.filters.computer_name),
-            |r: &Record| body_modifier(r, text(&r.computer_name)),
-            120,
-        ),
-        LogColumn::new(
-            header_impl("Process ID", &log_table.filters.process_id),
-            |r: &Record| body_modifier(r, text(&r.process_id)),
-            100,
-        ),
-        LogColumn::new(
-            header_impl("Process\nUser", &log_table.filters.process_user),
-            |r: &Record| body_modifier(r, text(&r.process_user)),
-            100,
-        ),
-        LogColumn::new(
-            header_impl("Module\nName", &log_table.filters.module_name),
-            |r: &Record| body_modifier(r, text(&r.module_name)),
-            120,
-        ),
-        LogColumn::new(
-            header_impl("Message ID", &log_table.filters.message_id),
-            |r: &Record| body_modifier(r, text(&r.message_id)),
-            100,
-        ),
-        LogColumn::new(
-            header_impl("Log\nLevel", &log_table.filters.log_level),
-            |r: &Record| body_modifier(r, text(&r.log_level)),
-            80,
-        ),
-        LogColumn::new(
-            header_impl("Facility", &log_table.filters.facility),
-            |r: &Record| body_modifier(r, text(&r.facility)),
-            70,
-        ),
-        LogColumn::new(
-            header_impl("Log\nMessage", &log_table.filters.log_message),
-            |r: &Record| body_modifier(r, text(&r.log_message).wrapping(text::Wrapping::None)),
-            Fill,
-        ),
-    ];
+    .width(SCROLLBAR_WIDTH);
 
-    let (mut columns, views): (Vec<_>, Vec<_>) = columns_def
-        .into_iter()
-        .map(|item| ((vec![item.header], item.width), item.view))
+    // Only the rows in view are built, the first one may be partly scrolled out
+    let events_filtered = &log_table.events_filtered;
+    let start = (log_table.scroll_value as usize).min(events_filtered.len());
+    let offset = events_filtered.get(start).map_or(0.0, |&event_idx| {
+        log_table.scroll_value.fract() as f32 * log_table._row_height(event_idx)
+    });
+    let end = (start + log_table._rows_fitting(start) + 1).min(events_filtered.len());
+    let records: Vec<&Record> = events_filtered[start..end]
+        .iter()
+        .map(|&event_idx| &log_table.events[event_idx])
+        .collect();
+    // The colors are the same for all cells of a row
+    let row_colors: Vec<RowColors<'a>> = records
+        .iter()
+        .map(|&record| RowColors {
+            severity: log_table.severity.level_for(record),
+            rule: log_table.rules.colors(record),
+            mark: log_table
+                .selected_rows
+                .get(record.id)
+                .map(|mark| mark.color.color()),
+        })
         .collect();
 
-    for (i, item_row) in items.enumerate() {
-        if (i as f64) >= (log_table.scroll_value)
-            && (i as f64) <= ((log_table.scroll_value) + log_table.rows_visible as f64)
-        {
-            let rec = log_table.events.get(*item_row).unwrap();
-
-            for (idx, (col, _width)) in columns.iter_mut().enumerate() {
-                col.push((views[idx])(rec));
-            }
-        }
-    }
-
-    let view_row = Row::with_children(
-        columns
-            .into_iter()
-            .map(|(column_containers, width)| {
-                let column = Column::with_children(
-                    column_containers
-                        .into_iter()
-                        .map(Into::into)
-                        .collect::<Vec<_>>(),
-                )
-                .width(width);
-                column.into()
-            })
-            .collect::<Vec<_>>(),
-    );
+    let view_row = Row::with_children(log_table.columns.visible().map(|column| {
+        let field = column.field;
+        let cells = Column::with_children(
+            records
+                .iter()
+                .zip(&row_colors)
+                .map(|(&r, &colors)| body_modifier(r, colors, field).into()),
+        );
+        let body: Element<'_, LogReaderMessage> = if field == Field::LogMessage {
+            // Long messages scroll sideways, the spacer keeps short rows as wide as the view
+            scrollable(virtual_list(
+                cells.push(iced::widget::space().width(message_width).height(0)),
+                offset,
+            ))
+            .direction(scrollable::Direction::Horizontal(
+                scrollable::Scrollbar::new().width(6).scroller_width(6),
+            ))
+            .height(Fill)
+            .into()
+        } else {
+            virtual_list(cells, offset).into()
+        };
+        column![header_impl(field), body]
+            .width(column.width.map_or(Fill, iced::Length::Fixed))
+            .into()
+    }));
 
     row![
         container(
@@ -562,6 +1192,6 @@ fn _build_table(log_table: &LogReader) -> Row<'_, LogReaderMessage> {
                 .on_exit(LogReaderMessage::TableLeft)
         )
         .clip(true),
-        container(slider).align_right(10)
+        scrollbar
     ]
 }
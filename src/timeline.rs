@@ -0,0 +1,172 @@
+use chrono::{DateTime, Local, TimeDelta, TimeZone};
+
+/// Bucket sizes in seconds the histogram picks from, so bars start on round times.
+const BUCKET_SIZES: [i64; 21] = [
+    1,
+    2,
+    5,
+    10,
+    15,
+    30,
+    60,
+    2 * 60,
+    5 * 60,
+    10 * 60,
+    15 * 60,
+    30 * 60,
+    3600,
+    2 * 3600,
+    3 * 3600,
+    6 * 3600,
+    12 * 3600,
+    86400,
+    2 * 86400,
+    7 * 86400,
+    30 * 86400,
+];
+// Zooming in stops when the view spans this many seconds
+const MIN_SPAN: i64 = 10;
+
+/// Record counts per time bucket, split by severity level. The last count of every bucket
+/// is for records without a level.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub start: DateTime<Local>,
+    pub bucket: TimeDelta,
+    pub counts: Vec<Vec<u32>>,
+}
+
+/// The smallest bucket size that splits `span` into at most `max_buckets` buckets.
+pub fn bucket_size(span: TimeDelta, max_buckets: usize) -> TimeDelta {
+    let max_buckets = max_buckets.max(1) as i64;
+    let seconds = BUCKET_SIZES
+        .into_iter()
+        .find(|&size| span.num_seconds() / size < max_buckets)
+        .unwrap_or(BUCKET_SIZES[BUCKET_SIZES.len() - 1]);
+    TimeDelta::seconds(seconds)
+}
+
+/// The start of the bucket `date` falls in, counted in local time so days start at midnight.
+fn align(date: DateTime<Local>, bucket: TimeDelta) -> DateTime<Local> {
+    let local_ms = date.naive_local().and_utc().timestamp_millis();
+    let bucket_ms = bucket.num_milliseconds();
+    let aligned = DateTime::from_timestamp_millis(local_ms - local_ms.rem_euclid(bucket_ms))
+        .unwrap_or_default()
+        .naive_utc();
+    Local
+        .from_local_datetime(&aligned)
+        .earliest()
+        .unwrap_or(date)
+}
+
+impl Histogram {
+    /// Counts the records, given by their date and severity level index, that fall in
+    /// `range`. `levels` is the number of severity levels.
+    pub fn build(
+        records: impl Iterator<Item = (DateTime<Local>, Option<usize>)>,
+        range: (DateTime<Local>, DateTime<Local>),
+        levels: usize,
+        max_buckets: usize,
+    ) -> Self {
+        let bucket = bucket_size(range.1 - range.0, max_buckets);
+        let start = align(range.0, bucket);
+        let len = ((range.1 - start).num_milliseconds() / bucket.num_milliseconds() + 1) as usize;
+
+        let mut histogram = Self {
+            start,
+            bucket,
+            counts: vec![vec![0; levels + 1]; len],
+        };
+        for (date, level) in records {
+            if let Some(idx) = histogram.bucket_of(date) {
+                histogram.counts[idx][level.unwrap_or(levels)] += 1;
+            }
+        }
+        histogram
+    }
+
+    pub fn bucket_of(&self, date: DateTime<Local>) -> Option<usize> {
+        let ms = (date - self.start).num_milliseconds();
+        if ms < 0 {
+            return None;
+        }
+        let idx = (ms / self.bucket.num_milliseconds()) as usize;
+        (idx < self.counts.len()).then_some(idx)
+    }
+
+    /// Start and end of the bucket, the end being the start of the next one.
+    pub fn bucket_range(&self, idx: usize) -> (DateTime<Local>, DateTime<Local>) {
+        let start = self.start + self.bucket * idx as i32;
+        (start, start + self.bucket)
+    }
+
+    pub fn end(&self) -> DateTime<Local> {
+        self.bucket_range(self.counts.len()).0
+    }
+
+    pub fn max_total(&self) -> u32 {
+        self.counts
+            .iter()
+            .map(|counts| counts.iter().sum())
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+/// Narrows the view by `factor` below 1 or widens it above 1, keeping the time at `at`,
+/// from 0 (start) to 1 (end), in place.
+pub fn zoom(
+    range: (DateTime<Local>, DateTime<Local>),
+    factor: f64,
+    at: f64,
+) -> (DateTime<Local>, DateTime<Local>) {
+    let span = (range.1 - range.0).num_milliseconds() as f64;
+    let new_span = (span * factor).max(MIN_SPAN as f64 * 1000.0);
+    let pivot = range.0 + TimeDelta::milliseconds((span * at.clamp(0.0, 1.0)) as i64);
+    let start = pivot - TimeDelta::milliseconds((new_span * at.clamp(0.0, 1.0)) as i64);
+    (start, start + TimeDelta::milliseconds(new_span as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_histogram() {
+        assert_eq!(
+            bucket_size(TimeDelta::minutes(10), 100),
+            TimeDelta::seconds(10)
+        );
+        assert_eq!(
+            bucket_size(TimeDelta::hours(20), 100),
+            TimeDelta::minutes(15)
+        );
+        assert_eq!(bucket_size(TimeDelta::days(9999), 10), TimeDelta::days(30));
+
+        let start = Local.with_ymd_and_hms(2024, 5, 1, 10, 0, 7).unwrap();
+        let at = |seconds| start + TimeDelta::seconds(seconds);
+        let records = [
+            (at(0), Some(0)),
+            (at(5), Some(1)),
+            (at(6), None),
+            (at(61), Some(1)),
+            (at(3600), Some(0)),
+        ];
+        let histogram = Histogram::build(records.into_iter(), (at(0), at(61)), 2, 20);
+
+        // 5 second buckets starting on a round time, the last record is out of range
+        assert_eq!(histogram.bucket, TimeDelta::seconds(5));
+        assert_eq!(histogram.start, at(-2));
+        assert_eq!(histogram.counts.len(), 13);
+        assert_eq!(histogram.counts[0], vec![1, 0, 0]);
+        assert_eq!(histogram.counts[1], vec![0, 1, 1]);
+        assert_eq!(histogram.counts[12], vec![0, 1, 0]);
+        assert_eq!(histogram.max_total(), 2);
+        assert_eq!(histogram.bucket_of(at(3600)), None);
+        assert_eq!(histogram.bucket_range(2), (at(8), at(13)));
+
+        let zoomed = zoom((at(0), at(100)), 0.5, 0.2);
+        assert_eq!(zoomed, (at(10), at(60)));
+        assert_eq!(zoom(zoomed, 0.01, 0.0), (at(10), at(20)));
+    }
+}
@@ -0,0 +1,354 @@
+use iced::advanced::widget::{Tree, tree};
+use iced::advanced::{Clipboard, Layout, Shell, Widget, layout, mouse, renderer};
+use iced::{
+    Background, Color, Element, Event, Length,
+    Length::Fill,
+    Rectangle, Size, Theme,
+    widget::{button, column, container, row, text},
+};
+use log_reader_core::DATE_FORMAT;
+
+use crate::LogReader;
+use crate::log_message::{LogReaderMessage, TimelineMessage};
+use crate::severity::SeverityLevel;
+use crate::table_view::apply_filter;
+use crate::timeline::Histogram;
+
+pub const TIMELINE_HEIGHT: f32 = 90.0;
+// Bars are never narrower than this, which caps the bucket count by the window width
+const MIN_BAR_WIDTH: f32 = 6.0;
+// Zoom per wheel line, in and out
+const ZOOM_STEP: f64 = 0.8;
+const PIXELS_PER_LINE: f32 = 40.0;
+
+/// Most buckets the histogram splits the shown time range into.
+pub fn max_buckets(window_width: f32) -> usize {
+    ((window_width - 16.0) / MIN_BAR_WIDTH).max(1.0) as usize
+}
+
+/// Record counts over time as stacked bars, one color per severity level. Clicking a bar
+/// or dragging across bars sends the bar indexes, the wheel zooms around the cursor.
+struct Bars<'a> {
+    histogram: &'a Histogram,
+    levels: &'a [SeverityLevel],
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// First and current bar while dragging
+    drag: Option<(usize, usize)>,
+}
+
+impl Bars<'_> {
+    fn bar_width(&self, bounds: Rectangle) -> f32 {
+        bounds.width / self.histogram.counts.len().max(1) as f32
+    }
+
+    fn bar_at(&self, bounds: Rectangle, x: f32) -> usize {
+        let idx = ((x - bounds.x) / self.bar_width(bounds)).max(0.0) as usize;
+        idx.min(self.histogram.counts.len().saturating_sub(1))
+    }
+}
+
+impl Widget<LogReaderMessage, Theme, iced::Renderer> for Bars<'_> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size {
+            width: Length::Fill,
+            height: Length::Fill,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _tree: &mut Tree,
+        _renderer: &iced::Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        layout::atomic(limits, Length::Fill, Length::Fill)
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &iced::Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, LogReaderMessage>,
+        _viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_mut::<State>();
+        let bounds = layout.bounds();
+        let msg = LogReaderMessage::Timeline;
+        if self.histogram.counts.is_empty() {
+            return;
+        }
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let Some(position) = cursor.position_over(bounds) else {
+                    return;
+                };
+                let bar = self.bar_at(bounds, position.x);
+                state.drag = Some((bar, bar));
+                shell.capture_event();
+                shell.request_redraw();
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                let Some((first, _)) = state.drag else {
+                    return;
+                };
+                state.drag = Some((first, self.bar_at(bounds, position.x)));
+                shell.capture_event();
+                shell.request_redraw();
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left)) => {
+                let Some((first, last)) = state.drag.take() else {
+                    return;
+                };
+                shell.publish(if first == last {
+                    msg(TimelineMessage::Clicked(first))
+                } else {
+                    msg(TimelineMessage::Selected(first.min(last), first.max(last)))
+                });
+                shell.capture_event();
+            }
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let Some(position) = cursor.position_over(bounds) else {
+                    return;
+                };
+                let lines = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => *y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_LINE,
+                };
+                let at = ((position.x - bounds.x) / bounds.width) as f64;
+                shell.publish(msg(TimelineMessage::Zoom(ZOOM_STEP.powf(lines as f64), at)));
+                shell.capture_event();
+            }
+            _ => (),
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        _tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        _renderer: &iced::Renderer,
+    ) -> mouse::Interaction {
+        if cursor.is_over(layout.bounds()) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut iced::Renderer,
+        theme: &Theme,
+        _style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+    ) {
+        use iced::advanced::Renderer as _;
+
+        let bounds = layout.bounds();
+        let palette = theme.extended_palette();
+        let fill = |renderer: &mut iced::Renderer, bounds: Rectangle, color: Color| {
+            renderer.fill_quad(
+                renderer::Quad {
+                    bounds,
+                    ..renderer::Quad::default()
+                },
+                Background::Color(color),
+            );
+        };
+
+        fill(renderer, bounds, palette.background.weak.color);
+
+        let max = self.histogram.max_total();
+        if max == 0 {
+            return;
+        }
+        // Records without a level at the bottom, then the levels in the order of the scheme.
+        // The counts can be of an older scheme while the levels are being edited.
+        let other = self.histogram.counts[0].len() - 1;
+        let level_color = |idx: usize| match self.levels.get(idx) {
+            Some(level) if idx != other => level
+                .colors(palette.is_dark)
+                .0
+                .unwrap_or(palette.primary.base.color),
+            _ => palette.background.strongest.color,
+        };
+        let order: Vec<usize> = std::iter::once(other).chain(0..other).collect();
+
+        let width = self.bar_width(bounds);
+        let gap = if width > 3.0 { 1.0 } else { 0.0 };
+        let hovered = cursor
+            .position_over(bounds)
+            .map(|position| self.bar_at(bounds, position.x));
+        for (idx, counts) in self.histogram.counts.iter().enumerate() {
+            let x = bounds.x + idx as f32 * width;
+            let mut bottom = bounds.y + bounds.height;
+            for &level in &order {
+                let height = bounds.height * counts[level] as f32 / max as f32;
+                if height <= 0.0 {
+                    continue;
+                }
+                bottom -= height;
+                let bar = Rectangle::new([x, bottom].into(), Size::new(width - gap, height));
+                fill(renderer, bar, level_color(level));
+            }
+            if hovered == Some(idx) {
+                let column = Rectangle::new([x, bounds.y].into(), Size::new(width, bounds.height));
+                fill(
+                    renderer,
+                    column,
+                    Color {
+                        a: 0.2,
+                        ..palette.primary.strong.color
+                    },
+                );
+            }
+        }
+
+        if let Some((first, last)) = tree.state.downcast_ref::<State>().drag {
+            let x = bounds.x + first.min(last) as f32 * width;
+            let selection = Rectangle::new(
+                [x, bounds.y].into(),
+                Size::new((first.abs_diff(last) + 1) as f32 * width, bounds.height),
+            );
+            fill(
+                renderer,
+                selection,
+                Color {
+                    a: 0.3,
+                    ..palette.primary.base.color
+                },
+            );
+        }
+    }
+}
+
+fn bucket_label(histogram: &Histogram) -> String {
+    let seconds = histogram.bucket.num_seconds();
+    match seconds {
+        s if s % 86400 == 0 => format!("{} d", s / 86400),
+        s if s % 3600 == 0 => format!("{} h", s / 3600),
+        s if s % 60 == 0 => format!("{} min", s / 60),
+        s => format!("{} s", s),
+    }
+}
+
+pub fn view(log_reader: &LogReader) -> Element<'_, LogReaderMessage> {
+    let msg = LogReaderMessage::Timeline;
+
+    let (header, bars): (String, Element<'_, LogReaderMessage>) = match &log_reader.histogram {
+        Some(histogram) => (
+            format!(
+                "{} \u{2013} {}   {} per bar, wheel to zoom, drag to filter",
+                histogram.start.format(DATE_FORMAT),
+                histogram.end().format(DATE_FORMAT),
+                bucket_label(histogram)
+            ),
+            Element::new(Bars {
+                histogram,
+                levels: &log_reader.severity.levels,
+            }),
+        ),
+        None => (String::from("No records"), text("").into()),
+    };
+
+    container(
+        column![
+            row![
+                text(header).size(12).width(Fill),
+                button(text("Reset zoom").size(12))
+                    .padding([0, 6])
+                    .style(button::secondary)
+                    .on_press_maybe(
+                        log_reader
+                            .timeline_zoom
+                            .is_some()
+                            .then(|| msg(TimelineMessage::ResetZoom))
+                    ),
+                button(text("Hide").size(12))
+                    .padding([0, 6])
+                    .style(button::secondary)
+                    .on_press(msg(TimelineMessage::Toggle)),
+            ]
+            .spacing(4)
+            .align_y(iced::Alignment::Center),
+            container(bars).height(Fill),
+        ]
+        .spacing(2),
+    )
+    .padding([4, 8])
+    .height(TIMELINE_HEIGHT)
+    .into()
+}
+
+/// The first shown row, in the order of the table, logged in the bucket.
+fn first_row_in(log_reader: &LogReader, bucket: usize) -> Option<usize> {
+    let histogram = log_reader.histogram.as_ref()?;
+    let (start, end) = histogram.bucket_range(bucket);
+    log_reader.events_filtered.iter().position(|&idx| {
+        let date = log_reader.events[idx].date;
+        date >= start && date < end
+    })
+}
+
+pub fn update(log_reader: &mut LogReader, msg: TimelineMessage) {
+    match msg {
+        TimelineMessage::Toggle => {
+            log_reader.timeline = !log_reader.timeline;
+            log_reader._update_rows_visible();
+        }
+        TimelineMessage::Clicked(bucket) => {
+            if let Some(pos) = first_row_in(log_reader, bucket) {
+                log_reader._jump_to(pos as u64);
+            }
+        }
+        TimelineMessage::Selected(first, last) => {
+            let Some(histogram) = &log_reader.histogram else {
+                return;
+            };
+            let (start, _) = histogram.bucket_range(first);
+            let (_, end) = histogram.bucket_range(last);
+            log_reader.history.push(log_reader._history_state());
+            log_reader.filters.date = start.to_rfc3339();
+            log_reader.filters.date_before = end.to_rfc3339();
+            log_reader.timeline_zoom = None;
+            apply_filter(log_reader);
+            log_reader._scroll_to(0);
+        }
+        TimelineMessage::Zoom(factor, at) => {
+            let Some(histogram) = &log_reader.histogram else {
+                return;
+            };
+            let range = log_reader
+                .timeline_zoom
+                .unwrap_or((histogram.start, histogram.end()));
+            log_reader.timeline_zoom = Some(crate::timeline::zoom(range, factor, at));
+        }
+        TimelineMessage::ResetZoom => log_reader.timeline_zoom = None,
+        TimelineMessage::RemoveDateBefore => {
+            log_reader.history.push(log_reader._history_state());
+            log_reader.filters.date_before.clear();
+            apply_filter(log_reader);
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use iced::advanced::widget::{Operation, Tree, tree};
+use iced::advanced::{Clipboard, Layout, Shell, Widget, layout, mouse, overlay, renderer};
+use iced::{Element, Event, Length, Point, Rectangle, Size, Theme, Vector};
+
+/// A window into a list that can be much longer than what fits on screen.
+///
+/// The caller builds only the rows in view and passes how many pixels of the first one are
+/// scrolled out of sight. The content is shifted up by that amount and clipped, so scrolling
+/// is pixel accurate while the cost depends only on the height of the view.
+pub struct VirtualList<'a, Message> {
+    content: Element<'a, Message>,
+    offset: f32,
+    width: Length,
+    height: Length,
+}
+
+pub fn virtual_list<'a, Message>(
+    content: impl Into<Element<'a, Message>>,
+    offset: f32,
+) -> VirtualList<'a, Message> {
+    VirtualList {
+        content: content.into(),
+        offset,
+        width: Length::Fill,
+        height: Length::Fill,
+    }
+}
+
+impl<Message> Widget<Message, Theme, iced::Renderer> for VirtualList<'_, Message> {
+    fn tag(&self) -> tree::Tag {
+        self.content.as_widget().tag()
+    }
+
+    fn state(&self) -> tree::State {
+        self.content.as_widget().state()
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        self.content.as_widget().children()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        self.content.as_widget().diff(tree);
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size {
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn layout(
+        &mut self,
+        tree: &mut Tree,
+        renderer: &iced::Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let limits = limits.width(self.width).height(self.height);
+
+        // The rows may be taller than the view, only the width is limited
+        let content_limits = layout::Limits::with_compression(
+            Size::ZERO,
+            Size::new(limits.max().width, f32::INFINITY),
+            Size::new(limits.compression().width, true),
+        );
+        let content = self
+            .content
+            .as_widget_mut()
+            .layout(tree, renderer, &content_limits)
+            .move_to(Point::new(0.0, -self.offset));
+
+        let size = limits.resolve(self.width, self.height, content.size());
+        layout::Node::with_children(size, vec![content])
+    }
+
+    fn operate(
+        &mut self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &iced::Renderer,
+        operation: &mut dyn Operation,
+    ) {
+        self.content.as_widget_mut().operate(
+            tree,
+            layout.children().next().unwrap(),
+            renderer,
+            operation,
+        );
+    }
+
+    fn update(
+        &mut self,
+        tree: &mut Tree,
+        event: &Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &iced::Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) {
+        // Rows scrolled out of the view must not react to the mouse
+        let cursor = if cursor.is_over(layout.bounds()) {
+            cursor
+        } else {
+            mouse::Cursor::Unavailable
+        };
+
+        self.content.as_widget_mut().update(
+            tree,
+            event,
+            layout.children().next().unwrap(),
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        );
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &iced::Renderer,
+    ) -> mouse::Interaction {
+        if !cursor.is_over(layout.bounds()) {
+            return mouse::Interaction::default();
+        }
+
+        self.content.as_widget().mouse_interaction(
+            tree,
+            layout.children().next().unwrap(),
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut iced::Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        let Some(clipped_viewport) = layout.bounds().intersection(viewport) else {
+            return;
+        };
+
+        renderer::Renderer::with_layer(renderer, clipped_viewport, |renderer| {
+            self.content.as_widget().draw(
+                tree,
+                renderer,
+                theme,
+                style,
+                layout.children().next().unwrap(),
+                cursor,
+                &clipped_viewport,
+            );
+        });
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'b>,
+        renderer: &iced::Renderer,
+        viewport: &Rectangle,
+        translation: Vector,
+    ) -> Option<overlay::Element<'b, Message, Theme, iced::Renderer>> {
+        self.content.as_widget_mut().overlay(
+            tree,
+            layout.children().next().unwrap(),
+            renderer,
+            viewport,
+            translation,
+        )
+    }
+}
+
+impl<'a, Message: 'a> From<VirtualList<'a, Message>> for Element<'a, Message> {
+    fn from(list: VirtualList<'a, Message>) -> Self {
+        Element::new(list)
+    }
+}